@@ -0,0 +1,95 @@
+//! Pooled against unpooled bullet scenes under a steady stream of fire.
+//!
+//! Every projectile is a scene instanced by the scene spawner, the same as a
+//! bullet model. There is no renderer, so the stand-in scene has no meshes or
+//! materials. Uploading and drawing those is left out of the numbers. What is
+//! measured is the entity churn: spawning scenes, copying their hierarchy in and
+//! despawning it again, against hiding and reusing pooled ones.
+
+use bevy::asset::AssetPlugin;
+use bevy::diagnostic::DiagnosticsPlugin;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy_tower_defence::{GameState, PoolKind, PoolPlugin, ProjectilePool, Released};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+/// Projectiles fired every frame, about what a late wave with every base built puts out.
+const VOLLEY: usize = 500;
+/// Child nodes in the stand-in bullet model, like the parts of a small glTF scene.
+const MODEL_PARTS: usize = 4;
+
+#[derive(Component)]
+struct InFlight;
+
+#[derive(Resource)]
+struct BulletScene(Handle<Scene>);
+
+/// A bullet-sized hierarchy of nodes with transforms and names.
+fn bullet_scene() -> Scene {
+    let mut world = World::new();
+    world
+        .spawn((TransformBundle::default(), Name::new("Bullet")))
+        .with_children(|parent| {
+            for _ in 0..MODEL_PARTS {
+                parent.spawn((TransformBundle::default(), Name::new("Part")));
+            }
+        });
+    Scene::new(world)
+}
+
+/// Releases last frame's volley and fires a new one, like towers firing non-stop.
+fn fire_volley(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    scene: Res<BulletScene>,
+    in_flight: Query<Entity, With<InFlight>>,
+) {
+    for entity in &in_flight {
+        commands
+            .entity(entity)
+            .remove::<InFlight>()
+            .insert(Released);
+    }
+    let kind = PoolKind::Scene(scene.0.clone());
+    for _ in 0..VOLLEY {
+        let entity = pool.acquire(&mut commands, &kind, Transform::default());
+        commands.entity(entity).insert(InFlight);
+    }
+}
+
+fn stress_app(pooled: bool) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(ScenePlugin)
+        .add_plugin(DiagnosticsPlugin)
+        // Never entering gameplay, so nothing is prewarmed from game assets
+        .add_state(GameState::MainMenu)
+        .add_plugin(PoolPlugin)
+        .insert_resource(ProjectilePool::new(pooled))
+        .add_system(fire_volley);
+    let scene = app
+        .world
+        .resource_mut::<Assets<Scene>>()
+        .add(bullet_scene());
+    app.insert_resource(BulletScene(scene));
+    // Let the pool fill up and the first scenes instance before measuring
+    for _ in 0..3 {
+        app.update();
+    }
+    app
+}
+
+fn projectile_pool(c: &mut Criterion) {
+    let mut group = c.benchmark_group("projectile_pool");
+    for (name, pooled) in [("pooled", true), ("unpooled", false)] {
+        let mut app = stress_app(pooled);
+        group.bench_function(name, |b| b.iter(|| app.update()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, projectile_pool);
+criterion_main!(benches);
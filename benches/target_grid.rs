@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy::utils::FloatOrd;
+use bevy_tower_defence::{GridEntry, Movement, TargetGrid};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const TOWERS: usize = 500;
+const TARGETS: usize = 5000;
+/// Width of the square the towers and targets are scattered over.
+const FIELD: f32 = 100.0;
+/// About the range of a typical tower.
+const RANGE: f32 = 4.0;
+
+fn scatter(rng: &mut StdRng, count: usize) -> Vec<Vec3> {
+    (0..count)
+        .map(|_| Vec3::new(rng.gen_range(0.0..FIELD), 0.0, rng.gen_range(0.0..FIELD)))
+        .collect()
+}
+
+fn entries(positions: &[Vec3]) -> Vec<GridEntry> {
+    positions
+        .iter()
+        .enumerate()
+        .map(|(index, &position)| GridEntry {
+            entity: Entity::from_raw(index as u32),
+            position,
+            movement: if index % 4 == 0 {
+                Movement::Flying
+            } else {
+                Movement::Ground
+            },
+        })
+        .collect()
+}
+
+fn target_grid(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(7);
+    let towers = scatter(&mut rng, TOWERS);
+    let targets = entries(&scatter(&mut rng, TARGETS));
+    let mut grid = TargetGrid::default();
+    for &entry in &targets {
+        grid.insert(entry);
+    }
+
+    let mut group = c.benchmark_group("target_grid");
+    group.bench_function("rebuild", |b| {
+        b.iter(|| {
+            grid.clear();
+            for &entry in &targets {
+                grid.insert(entry);
+            }
+        })
+    });
+    group.bench_function("closest", |b| {
+        b.iter(|| {
+            for &tower in &towers {
+                black_box(grid.closest(tower, RANGE, |entry| entry.movement == Movement::Ground));
+            }
+        })
+    });
+    group.bench_function("within", |b| {
+        b.iter(|| {
+            for &tower in &towers {
+                black_box(grid.within(tower, RANGE).count());
+            }
+        })
+    });
+    // What every tower scanning every target would cost without the grid
+    group.bench_function("closest_brute_force", |b| {
+        b.iter(|| {
+            for &tower in &towers {
+                black_box(
+                    targets
+                        .iter()
+                        .filter(|entry| entry.movement == Movement::Ground)
+                        .filter(|entry| entry.position.distance(tower) < RANGE)
+                        .min_by_key(|entry| FloatOrd(entry.position.distance(tower))),
+                );
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, target_grid);
+criterion_main!(benches);
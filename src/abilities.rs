@@ -0,0 +1,341 @@
+use crate::{
+    cancel_placement, hotkey_button_tint, pressed_hotkey_buttons, spawn_hotkey_bar, Armed,
+    ArmedPlacement, CanTarget, Currency, CursorGround, ExplosionEvent, Explosive, Funds, GameState,
+    HotkeyButton, HotkeyStatusText, Player, StatusEffect, StatusEvent, Target,
+};
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+const METEOR_RADIUS: f32 = 3.0;
+const METEOR_DAMAGE: f32 = 8.0;
+const FREEZE_DURATION: f32 = 4.0;
+const GOLD_RUSH_DURATION: f32 = 15.0;
+const GOLD_RUSH_MULTIPLIER: u32 = 2;
+
+/// Spells the player casts directly instead of building, also marks their button on the bar.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ability {
+    MeteorStrike,
+    Freeze,
+    GoldRush,
+}
+
+impl HotkeyButton for Ability {
+    const ALL: &'static [Ability] = &[Ability::MeteorStrike, Ability::Freeze, Ability::GoldRush];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Ability::MeteorStrike => "Meteor",
+            Ability::Freeze => "Freeze",
+            Ability::GoldRush => "Gold rush",
+        }
+    }
+
+    fn hotkey(&self) -> KeyCode {
+        match self {
+            Ability::MeteorStrike => KeyCode::Key1,
+            Ability::Freeze => KeyCode::Key2,
+            Ability::GoldRush => KeyCode::Key3,
+        }
+    }
+
+    fn hotkey_label(&self) -> &'static str {
+        match self {
+            Ability::MeteorStrike => "1",
+            Ability::Freeze => "2",
+            Ability::GoldRush => "3",
+        }
+    }
+
+    fn cost(&self) -> Funds {
+        match self {
+            Ability::MeteorStrike => Funds::gold(50),
+            Ability::Freeze => Funds::new(&[(Currency::Gold, 30), (Currency::Crystals, 1)]),
+            Ability::GoldRush => Funds::new(&[(Currency::Crystals, 2)]),
+        }
+    }
+}
+
+impl Ability {
+    /// Seconds before it can be cast again.
+    pub fn cooldown(&self) -> f32 {
+        match self {
+            Ability::MeteorStrike => 20.0,
+            Ability::Freeze => 30.0,
+            Ability::GoldRush => 45.0,
+        }
+    }
+
+    /// Cast at a point picked on the ground rather than straight away.
+    fn needs_target(&self) -> bool {
+        matches!(self, Ability::MeteorStrike)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Abilities {
+    /// Seconds left on each ability still cooling down
+    cooldowns: HashMap<Ability, f32>,
+}
+
+impl Abilities {
+    pub fn cooldown_remaining(&self, ability: Ability) -> f32 {
+        self.cooldowns.get(&ability).copied().unwrap_or(0.0)
+    }
+}
+
+/// Kill bounties are multiplied while this is running.
+#[derive(Resource, Default)]
+pub struct GoldRush {
+    pub remaining: f32,
+}
+
+impl GoldRush {
+    pub fn bounty_multiplier(&self) -> u32 {
+        match self.remaining > 0.0 {
+            true => GOLD_RUSH_MULTIPLIER,
+            false => 1,
+        }
+    }
+}
+
+pub struct AbilityCastEvent {
+    pub ability: Ability,
+    /// Where it was aimed, for abilities that need a target
+    pub target: Option<Vec3>,
+}
+
+#[derive(Component)]
+pub struct AbilityBarRoot;
+
+/// Follows the cursor over the ground showing where a meteor would land.
+#[derive(Component)]
+pub struct AimRing;
+
+pub struct AbilityPlugin;
+
+impl Plugin for AbilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Abilities>()
+            .init_resource::<GoldRush>()
+            .add_event::<AbilityCastEvent>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::Gameplay)
+                    .with_system(spawn_ability_bar)
+                    .with_system(spawn_aim_ring),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(tick_ability_timers)
+                    // Before the click that starts aiming could be taken as the target
+                    .with_system(aim_abilities.after(cancel_placement).before(ability_input))
+                    .with_system(ability_input)
+                    .with_system(cast_abilities.after(aim_abilities).after(ability_input))
+                    .with_system(update_ability_bar),
+            );
+    }
+}
+
+fn spawn_ability_bar(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/Merriweather-Regular.ttf");
+    let bar = spawn_hotkey_bar::<Ability>(
+        &mut commands,
+        font,
+        Style {
+            size: Size::new(Val::Percent(100.0), Val::Auto),
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Percent(7.0),
+                ..default()
+            },
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+    );
+    commands
+        .entity(bar)
+        .insert(AbilityBarRoot)
+        .insert(Name::new("Ability Bar"));
+}
+
+fn spawn_aim_ring(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Torus {
+                radius: METEOR_RADIUS,
+                ring_radius: 0.05,
+                subdivisions_segments: 64,
+                subdivisions_sides: 6,
+            })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(1.0, 0.3, 0.1, 0.8),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::INVISIBLE,
+            ..default()
+        })
+        .insert(NotShadowCaster)
+        .insert(AimRing)
+        .insert(Name::new("Aim Ring"));
+}
+
+fn tick_ability_timers(
+    mut abilities: ResMut<Abilities>,
+    mut gold_rush: ResMut<GoldRush>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    if !abilities.cooldowns.is_empty() {
+        for remaining in abilities.cooldowns.values_mut() {
+            *remaining -= delta;
+        }
+        abilities.cooldowns.retain(|_, remaining| *remaining > 0.0);
+    }
+    if gold_rush.remaining > 0.0 {
+        gold_rush.remaining -= delta;
+    }
+}
+
+fn ability_input(
+    interactions: Query<(&Interaction, &Ability), Changed<Interaction>>,
+    keyboard: Res<Input<KeyCode>>,
+    abilities: Res<Abilities>,
+    mut placement: ResMut<ArmedPlacement>,
+    player: Query<&Player>,
+    mut cast_events: EventWriter<AbilityCastEvent>,
+) {
+    let player = player.single();
+    for ability in pressed_hotkey_buttons(&interactions, &keyboard) {
+        if abilities.cooldown_remaining(ability) > 0.0 || !player.funds.can_afford(&ability.cost())
+        {
+            continue;
+        }
+        if ability.needs_target() {
+            placement.toggle(Armed::Ability(ability));
+        } else {
+            cast_events.send(AbilityCastEvent {
+                ability,
+                target: None,
+            });
+        }
+    }
+}
+
+fn aim_abilities(
+    mut placement: ResMut<ArmedPlacement>,
+    cursor: CursorGround,
+    mouse: Res<Input<MouseButton>>,
+    mut ring: Query<(&mut Transform, &mut Visibility), With<AimRing>>,
+    mut cast_events: EventWriter<AbilityCastEvent>,
+) {
+    let Ok((mut ring_transform, mut ring_visibility)) = ring.get_single_mut() else {
+        return;
+    };
+    let Some(ability) = placement.ability() else {
+        ring_visibility.is_visible = false;
+        return;
+    };
+    let Some(point) = cursor.point() else {
+        ring_visibility.is_visible = false;
+        return;
+    };
+
+    ring_transform.translation = point + Vec3::Y * 0.05;
+    ring_visibility.is_visible = true;
+    if mouse.just_pressed(MouseButton::Left) {
+        cast_events.send(AbilityCastEvent {
+            ability,
+            target: Some(point),
+        });
+        placement.armed = None;
+    }
+}
+
+fn cast_abilities(
+    mut cast_events: EventReader<AbilityCastEvent>,
+    mut abilities: ResMut<Abilities>,
+    mut gold_rush: ResMut<GoldRush>,
+    mut player: Query<&mut Player>,
+    targets: Query<Entity, With<Target>>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut status_events: EventWriter<StatusEvent>,
+) {
+    let mut player = player.single_mut();
+    for cast in cast_events.iter() {
+        // Checked again in case something else spent the money since it was picked
+        if abilities.cooldown_remaining(cast.ability) > 0.0
+            || !player.funds.spend(&cast.ability.cost())
+        {
+            continue;
+        }
+        abilities
+            .cooldowns
+            .insert(cast.ability, cast.ability.cooldown());
+
+        match cast.ability {
+            Ability::MeteorStrike => {
+                let Some(position) = cast.target else {
+                    continue;
+                };
+                explosion_events.send(ExplosionEvent {
+                    position,
+                    explosive: Explosive {
+                        radius: METEOR_RADIUS,
+                        damage: METEOR_DAMAGE,
+                        falloff: 0.5,
+                        detonation_point: None,
+                        // Falls from the sky, catching flyers on the way down
+                        can_target: CanTarget {
+                            ground: true,
+                            air: true,
+                        },
+                    },
+                    source: None,
+                });
+            }
+            Ability::Freeze => {
+                for target in &targets {
+                    status_events.send(StatusEvent {
+                        target,
+                        effect: StatusEffect {
+                            name: "Frozen",
+                            speed_multiplier: 0.0,
+                            remaining: FREEZE_DURATION,
+                        },
+                    });
+                }
+            }
+            Ability::GoldRush => gold_rush.remaining = GOLD_RUSH_DURATION,
+        }
+    }
+}
+
+fn update_ability_bar(
+    abilities: Res<Abilities>,
+    placement: Res<ArmedPlacement>,
+    player: Query<&Player>,
+    mut buttons: Query<(&Ability, &mut BackgroundColor)>,
+    mut texts: Query<(&HotkeyStatusText<Ability>, &mut Text)>,
+) {
+    let player = player.single();
+    for (ability, mut tint) in &mut buttons {
+        let ready = abilities.cooldown_remaining(*ability) <= 0.0
+            && player.funds.can_afford(&ability.cost());
+        *tint = hotkey_button_tint(placement.ability() == Some(*ability), ready);
+    }
+    for (status, mut text) in &mut texts {
+        let cooldown = abilities.cooldown_remaining(status.button);
+        text.sections[0].value = if cooldown > 0.0 {
+            format!("Ready in {:.0}s", cooldown.ceil())
+        } else {
+            status.button.cost().to_string()
+        };
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    AirPath, ExplosionEvent, Explosive, GameState, PoolKind, ProjectilePool, Released,
+    StatusEffects, Target, TargetPath,
+};
+use bevy::prelude::*;
+
+/// Lobs shells on a parabolic arc to where the target will be when they land.
+#[derive(Clone, Copy)]
+pub struct Artillery {
+    /// Angle above the horizontal shells are launched at, in radians
+    pub launch_angle: f32,
+    pub gravity: f32,
+    /// Targets closer than this are too close to lob a shell at
+    pub min_range: f32,
+    pub explosive: Explosive,
+}
+
+impl Artillery {
+    /// Launch velocity and flight time to land a shell on `point`, or None if
+    /// the point can't be reached at this launch angle.
+    pub fn solve(&self, origin: Vec3, point: Vec3) -> Option<(Vec3, f32)> {
+        let offset = point - origin;
+        let horizontal = Vec3::new(offset.x, 0.0, offset.z);
+        let distance = horizontal.length();
+        let (sin, cos) = self.launch_angle.sin_cos();
+
+        // From y = x tan(a) - g x^2 / (2 v^2 cos^2(a)) solved for v at x = distance, y = offset.y
+        let denominator = 2.0 * cos * cos * (distance * sin / cos - offset.y);
+        if distance <= f32::EPSILON || denominator <= 0.0 {
+            return None;
+        }
+        let speed = (self.gravity * distance * distance / denominator).sqrt();
+        let flight_time = distance / (speed * cos);
+        let velocity = horizontal / distance * speed * cos + Vec3::Y * speed * sin;
+        Some((velocity, flight_time))
+    }
+}
+
+pub struct ArtilleryEvent {
+    pub tower: Entity,
+    pub origin: Vec3,
+    pub target: Entity,
+    pub artillery: Artillery,
+}
+
+#[derive(Component)]
+pub struct Shell {
+    pub origin: Vec3,
+    pub velocity: Vec3,
+    pub gravity: f32,
+    pub elapsed: f32,
+    pub flight_time: f32,
+    pub landing_point: Vec3,
+    pub explosive: Explosive,
+    /// The tower that fired it
+    pub source: Option<Entity>,
+}
+
+#[derive(Resource)]
+pub struct ArtilleryAssets {
+    shell_mesh: Handle<Mesh>,
+    shell_material: Handle<StandardMaterial>,
+}
+
+pub struct ArtilleryPlugin;
+
+impl Plugin for ArtilleryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ArtilleryEvent>()
+            .add_startup_system(artillery_asset_loading)
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(fire_shells)
+                    .with_system(shell_flight),
+            );
+    }
+}
+
+fn artillery_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ArtilleryAssets {
+        shell_mesh: meshes.add(Mesh::from(shape::Icosphere {
+            radius: 0.1,
+            subdivisions: 2,
+        })),
+        shell_material: materials.add(Color::rgb(0.15, 0.15, 0.15).into()),
+    });
+}
+
+fn fire_shells(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    assets: Res<ArtilleryAssets>,
+    path: Res<TargetPath>,
+    air_path: Res<AirPath>,
+    targets: Query<(&Target, &StatusEffects)>,
+    mut artillery_events: EventReader<ArtilleryEvent>,
+) {
+    for event in artillery_events.iter() {
+        let Ok((target, effects)) = targets.get(event.target) else {
+            continue;
+        };
+        let route = target.movement.route(&path, &air_path);
+        // Slowed, frozen or held up targets don't get as far as their speed says
+        let speed = target.current_speed(effects);
+        let predicted_point =
+            |flight_time: f32| route.position_at(target.distance + speed * flight_time);
+
+        // The flight time depends on the landing point and the landing point on
+        // the flight time, a few rounds of refining is plenty to converge
+        let mut flight_time = 0.0;
+        for _ in 0..3 {
+            match event
+                .artillery
+                .solve(event.origin, predicted_point(flight_time))
+            {
+                Some((_, time)) => flight_time = time,
+                None => break,
+            }
+        }
+
+        let landing_point = predicted_point(flight_time);
+        let Some((velocity, flight_time)) = event.artillery.solve(event.origin, landing_point)
+        else {
+            continue;
+        };
+
+        let kind = PoolKind::Mesh(assets.shell_mesh.clone(), assets.shell_material.clone());
+        let shell = pool.acquire(
+            &mut commands,
+            &kind,
+            Transform::from_translation(event.origin),
+        );
+        commands
+            .entity(shell)
+            .insert(Shell {
+                origin: event.origin,
+                velocity,
+                gravity: event.artillery.gravity,
+                elapsed: 0.0,
+                flight_time,
+                landing_point,
+                explosive: event.artillery.explosive,
+                source: Some(event.tower),
+            })
+            .insert(Name::new("Shell"));
+    }
+}
+
+fn shell_flight(
+    mut commands: Commands,
+    mut shells: Query<(Entity, &mut Shell, &mut Transform)>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut shell, mut transform) in &mut shells {
+        shell.elapsed += time.delta_seconds();
+        if shell.elapsed >= shell.flight_time {
+            commands.entity(entity).insert(Released);
+            explosion_events.send(ExplosionEvent {
+                position: shell.landing_point,
+                explosive: shell.explosive,
+                source: shell.source,
+            });
+            continue;
+        }
+
+        let t = shell.elapsed;
+        transform.translation =
+            shell.origin + shell.velocity * t - Vec3::Y * 0.5 * shell.gravity * t * t;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{headless_app, send_event};
+    use crate::{CanTarget, StatusEffect, FLYING_ALTITUDE};
+
+    fn artillery_app() -> App {
+        let mut app = headless_app();
+        let path = TargetPath::from_control_points(&[Vec3::ZERO, Vec3::X * 20.0]);
+        app.insert_resource(AirPath::over(&path, FLYING_ALTITUDE))
+            .insert_resource(path)
+            .insert_resource(ProjectilePool::new(false))
+            .insert_resource(ArtilleryAssets {
+                shell_mesh: Handle::default(),
+                shell_material: Handle::default(),
+            })
+            .add_event::<ArtilleryEvent>()
+            .add_system(fire_shells);
+        app
+    }
+
+    /// Fires one shell at `target` from beside the path and returns where it will land.
+    fn landing_point(app: &mut App, target: Entity) -> Vec3 {
+        let tower = app.world.spawn_empty().id();
+        send_event(
+            app,
+            ArtilleryEvent {
+                tower,
+                origin: Vec3::new(10.0, 0.0, 5.0),
+                target,
+                artillery: Artillery {
+                    launch_angle: 60_f32.to_radians(),
+                    gravity: 9.8,
+                    min_range: 2.5,
+                    explosive: Explosive {
+                        radius: 2.0,
+                        damage: 3.0,
+                        falloff: 0.5,
+                        detonation_point: None,
+                        can_target: CanTarget {
+                            ground: true,
+                            air: false,
+                        },
+                    },
+                },
+            },
+        );
+        app.update();
+        let mut shells = app.world.query::<&Shell>();
+        shells.single(&app.world).landing_point
+    }
+
+    fn spawn_walker(app: &mut App, effects: StatusEffects, blocked_by: Option<Entity>) -> Entity {
+        let target = Target {
+            speed: 2.0,
+            distance: 8.0,
+            blocked_by,
+            ..default()
+        };
+        app.world.spawn((target, effects)).id()
+    }
+
+    #[test]
+    fn shells_lead_moving_targets() {
+        let mut app = artillery_app();
+        let walker = spawn_walker(&mut app, StatusEffects::default(), None);
+        assert!(landing_point(&mut app, walker).x > 8.5);
+    }
+
+    #[test]
+    fn shells_land_on_frozen_targets() {
+        let mut app = artillery_app();
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect {
+            name: "Frozen",
+            speed_multiplier: 0.0,
+            remaining: 4.0,
+        });
+        let walker = spawn_walker(&mut app, effects, None);
+        let landing = landing_point(&mut app, walker);
+        assert!(landing.distance(Vec3::X * 8.0) < 1e-3);
+    }
+
+    #[test]
+    fn shells_land_on_blocked_targets() {
+        let mut app = artillery_app();
+        let blocker = app.world.spawn_empty().id();
+        let walker = spawn_walker(&mut app, StatusEffects::default(), Some(blocker));
+        let landing = landing_point(&mut app, walker);
+        assert!(landing.distance(Vec3::X * 8.0) < 1e-3);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    DamageEvent, DamageKind, GameAssets, GameState, Health, Movement, PathBlocker, Target,
+    TargetPath, Tower,
+};
+use bevy::prelude::*;
+use bevy::utils::FloatOrd;
+
+const SQUAD_SIZE: usize = 3;
+const SOLDIER_HEALTH: f32 = 6.0;
+/// Extra health for soldiers trained at each barracks level above the first.
+const SOLDIER_HEALTH_PER_LEVEL: f32 = 3.0;
+const SOLDIER_DAMAGE: f32 = 1.0;
+const SOLDIER_ATTACK_INTERVAL: f32 = 0.8;
+const SOLDIER_SPEED: f32 = 1.5;
+/// How close a soldier has to be to hit an enemy.
+const SOLDIER_REACH: f32 = 0.9;
+/// Gap between soldiers standing side by side across the path.
+const SQUAD_SPACING: f32 = 0.5;
+
+/// Trains a squad of soldiers to hold the nearest stretch of path, replacing any that fall.
+#[derive(Component, Default)]
+pub struct Barracks {
+    /// Where along the `TargetPath` the squad stands
+    pub rally_distance: f32,
+    /// One slot per soldier, empty until one is trained to fill it
+    pub squad: [Option<Entity>; SQUAD_SIZE],
+}
+
+/// Holds the path for its barracks, fighting walkers that come near its post.
+#[derive(Component)]
+pub struct Soldier {
+    pub barracks: Entity,
+    /// Spot on the path the soldier returns to between fights
+    pub post: Vec3,
+    rally_distance: f32,
+    attack: Timer,
+}
+
+pub struct BarracksPlugin;
+
+impl Plugin for BarracksPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(train_soldiers)
+                // After, so the new squad isn't taken for fallen before it has spawned
+                .with_system(muster_new_barracks.after(train_soldiers))
+                .with_system(soldier_combat)
+                .with_system(soldier_death),
+        );
+    }
+}
+
+/// Where the soldier in `slot` stands, spread across the path at the rally point.
+fn post_for(path: &TargetPath, rally_distance: f32, slot: usize) -> Vec3 {
+    let across = path
+        .direction_at(rally_distance)
+        .cross(Vec3::Y)
+        .normalize_or_zero();
+    let offset = slot as f32 - (SQUAD_SIZE - 1) as f32 / 2.0;
+    path.position_at(rally_distance) + across * offset * SQUAD_SPACING
+}
+
+fn spawn_soldier(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    barracks: Entity,
+    tower: &Tower,
+    from: Vec3,
+    post: Vec3,
+    rally_distance: f32,
+) -> Entity {
+    let health = SOLDIER_HEALTH + SOLDIER_HEALTH_PER_LEVEL * (tower.level - 1) as f32;
+    commands
+        .spawn(SceneBundle {
+            scene: assets.soldier_scene.clone(),
+            transform: Transform::from_translation(from),
+            ..default()
+        })
+        .insert(Soldier {
+            barracks,
+            post,
+            rally_distance,
+            attack: Timer::from_seconds(SOLDIER_ATTACK_INTERVAL, TimerMode::Repeating),
+        })
+        .insert(Health::new(health))
+        .insert(Name::new("Soldier"))
+        .id()
+}
+
+fn muster_new_barracks(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    path: Res<TargetPath>,
+    mut new_barracks: Query<(Entity, &mut Barracks, &Tower, &Transform), Added<Barracks>>,
+) {
+    for (entity, mut barracks, tower, transform) in &mut new_barracks {
+        barracks.rally_distance = path.nearest(transform.translation).0;
+        // The first squad turns out straight away
+        for slot in 0..SQUAD_SIZE {
+            let post = post_for(&path, barracks.rally_distance, slot);
+            let soldier = spawn_soldier(
+                &mut commands,
+                &assets,
+                entity,
+                tower,
+                transform.translation,
+                post,
+                barracks.rally_distance,
+            );
+            barracks.squad[slot] = Some(soldier);
+        }
+    }
+}
+
+fn train_soldiers(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    path: Res<TargetPath>,
+    mut all_barracks: Query<(Entity, &mut Barracks, &Tower, &Transform)>,
+    soldiers: Query<(), With<Soldier>>,
+) {
+    for (entity, mut barracks, tower, transform) in &mut all_barracks {
+        // Free up the slots of soldiers that have fallen
+        for slot in barracks.squad.iter_mut() {
+            if matches!(slot, Some(soldier) if !soldiers.contains(*soldier)) {
+                *slot = None;
+            }
+        }
+        // Ticked by tower_shooting, which never finds a target for a barracks
+        if !tower.shooting_timer.just_finished() {
+            continue;
+        }
+        let Some(slot) = barracks.squad.iter().position(Option::is_none) else {
+            continue;
+        };
+        let post = post_for(&path, barracks.rally_distance, slot);
+        let soldier = spawn_soldier(
+            &mut commands,
+            &assets,
+            entity,
+            tower,
+            transform.translation,
+            post,
+            barracks.rally_distance,
+        );
+        barracks.squad[slot] = Some(soldier);
+    }
+}
+
+fn soldier_combat(
+    mut commands: Commands,
+    mut soldiers: Query<(Entity, &mut Soldier, &mut Transform, Option<&PathBlocker>)>,
+    towers: Query<&Tower>,
+    targets: Query<(Entity, &Target, &Transform), Without<Soldier>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut soldier, mut transform, blocker) in &mut soldiers {
+        // The barracks' range is how far from their post soldiers will go to fight
+        let leash = towers
+            .get(soldier.barracks)
+            .map_or(0.0, |tower| tower.range);
+        let post = soldier.post;
+        let foe = targets
+            .iter()
+            .filter(|(_, target, foe_transform)| {
+                target.movement == Movement::Ground
+                    && foe_transform.translation.distance(post) <= leash
+            })
+            .map(|(foe, _, foe_transform)| (foe, foe_transform.translation))
+            .min_by_key(|(_, position)| FloatOrd(position.distance(transform.translation)));
+
+        let (goal, reach) = match foe {
+            Some((_, position)) => (position, SOLDIER_REACH),
+            None => (post, 0.05),
+        };
+        let to_goal = goal - transform.translation;
+        let in_reach = to_goal.length() <= reach;
+        if !in_reach {
+            let step = (SOLDIER_SPEED * time.delta_seconds()).min(to_goal.length() - reach);
+            transform.translation += to_goal.normalize() * step;
+        }
+
+        // Face where it is heading but stay upright
+        let facing = to_goal * Vec3::new(1.0, 0.0, 1.0);
+        if facing.length() > 0.01 {
+            let look_at = transform.translation + facing;
+            transform.look_at(look_at, Vec3::Y);
+        }
+
+        match foe {
+            Some((foe, _)) if in_reach => {
+                if soldier.attack.tick(time.delta()).just_finished() {
+                    damage_events.send(DamageEvent {
+                        target: foe,
+                        amount: SOLDIER_DAMAGE,
+                        kind: DamageKind::Physical,
+                        critical: false,
+                        // Kills are credited to the barracks
+                        source: Some(soldier.barracks),
+                    });
+                }
+            }
+            _ => soldier.attack.reset(),
+        }
+
+        // Walkers are only held up while the soldier is standing at its post
+        let at_post = transform.translation.distance(post) <= SOLDIER_REACH;
+        match (at_post, blocker.is_some()) {
+            (true, false) => {
+                commands.entity(entity).insert(PathBlocker {
+                    distance: soldier.rally_distance,
+                });
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<PathBlocker>();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn soldier_death(mut commands: Commands, soldiers: Query<(Entity, &Health), With<Soldier>>) {
+    for (entity, health) in &soldiers {
+        if health.value <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
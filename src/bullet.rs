@@ -1,11 +1,35 @@
-use crate::GameState;
+use crate::{CanTarget, GameState, Released, Shell, Target, TargetGrid};
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
 use bevy::prelude::*;
 
+/// Bullets and shells currently in flight.
+pub const PROJECTILE_COUNT: DiagnosticId =
+    DiagnosticId::from_u128(139882176480813049327218542934183409154);
+
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct Bullet {
     pub direction: Vec3,
     pub speed: f32,
+    pub damage: f32,
+    /// How far the bullet can fly before it's despawned
+    pub range: f32,
+    pub travelled: f32,
+    /// The tower that fired it
+    pub source: Option<Entity>,
+}
+
+/// Anything flying outside these bounds has left the map.
+#[derive(Resource)]
+pub struct MapBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl MapBounds {
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
 }
 
 #[derive(Reflect, Component, Default)]
@@ -14,23 +38,145 @@ pub struct Lifetime {
     pub timer: Timer,
 }
 
+/// Steers a bullet towards its target every frame instead of flying in a straight line.
+#[derive(Component)]
+pub struct Homing {
+    pub target: Option<Entity>,
+    /// Maximum change of direction in radians per second
+    pub turn_rate: f32,
+    /// How far from the bullet a new target may be if the current one dies mid-flight
+    pub retarget_range: f32,
+    /// What the firing tower can shoot, so a new target is one it could have picked
+    pub can_target: CanTarget,
+}
+
+/// Lets a bullet carry on through targets instead of stopping at the first one.
+#[derive(Component)]
+pub struct Pierce {
+    /// How many more targets the bullet can pass through
+    pub remaining: u32,
+    /// Targets already passed through, so they are only damaged once
+    pub hit: Vec<Entity>,
+}
+
+/// Bounces a bullet on to the nearest target it hasn't hit yet.
+#[derive(Component)]
+pub struct Ricochet {
+    pub remaining: u32,
+    /// How far away the next target may be
+    pub range: f32,
+    /// Fraction of the damage lost on every bounce
+    pub damage_falloff: f32,
+    pub hit: Vec<Entity>,
+}
+
 pub struct BulletPlugin;
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Bullet>()
             .register_type::<Lifetime>()
+            .insert_resource(MapBounds {
+                min: Vec3::new(-50.0, -1.0, -50.0),
+                max: Vec3::new(150.0, 50.0, 50.0),
+            })
+            .add_startup_system(projectile_diagnostics_setup)
+            .add_system(projectile_diagnostics)
             .add_system_set(
                 SystemSet::on_update(GameState::Gameplay)
+                    .with_system(bullet_homing.before(bullet_movement))
                     .with_system(bullet_movement)
+                    .with_system(bullet_expiry.after(bullet_movement))
                     .with_system(bullet_despawn),
             );
     }
 }
 
-fn bullet_movement(mut bullets: Query<(&Bullet, &mut Transform)>, time: Res<Time>) {
-    for (bullet, mut transform) in &mut bullets {
-        transform.translation += bullet.direction.normalize() * bullet.speed * time.delta_seconds();
+fn bullet_movement(mut bullets: Query<(&mut Bullet, &mut Transform)>, time: Res<Time>) {
+    for (mut bullet, mut transform) in &mut bullets {
+        let step = bullet.speed * time.delta_seconds();
+        transform.translation += bullet.direction.normalize() * step;
+        bullet.travelled += step;
+    }
+}
+
+fn bullet_expiry(
+    mut commands: Commands,
+    bullets: Query<(Entity, &Bullet, &Transform)>,
+    bounds: Res<MapBounds>,
+) {
+    for (entity, bullet, transform) in &bullets {
+        if bullet.travelled >= bullet.range || !bounds.contains(transform.translation) {
+            commands.entity(entity).insert(Released);
+        }
+    }
+}
+
+fn projectile_diagnostics_setup(mut diagnostics: ResMut<Diagnostics>) {
+    diagnostics.add(Diagnostic::new(PROJECTILE_COUNT, "projectile_count", 20));
+}
+
+fn projectile_diagnostics(
+    mut diagnostics: ResMut<Diagnostics>,
+    bullets: Query<(), With<Bullet>>,
+    shells: Query<(), With<Shell>>,
+) {
+    diagnostics.add_measurement(PROJECTILE_COUNT, || {
+        (bullets.iter().count() + shells.iter().count()) as f64
+    });
+}
+
+fn bullet_homing(
+    mut commands: Commands,
+    mut bullets: Query<(
+        Entity,
+        &mut Bullet,
+        &mut Homing,
+        &mut Transform,
+        &GlobalTransform,
+    )>,
+    targets: Query<&GlobalTransform, With<Target>>,
+    grid: Res<TargetGrid>,
+    time: Res<Time>,
+) {
+    for (entity, mut bullet, mut homing, mut transform, global_transform) in &mut bullets {
+        let position = global_transform.translation();
+
+        // The target died mid-flight, pick the closest one still alive nearby
+        let target_alive = homing.target.is_some_and(|target| targets.contains(target));
+        if !target_alive {
+            let can_target = homing.can_target;
+            homing.target = grid
+                .closest(position, homing.retarget_range, |entry| {
+                    can_target.allows(entry.movement)
+                })
+                .map(|entry| entry.entity);
+        }
+
+        let Some(Ok(target_transform)) = homing.target.map(|target| targets.get(target)) else {
+            // Nothing left to chase, fly on straight until out of range
+            commands.entity(entity).remove::<Homing>();
+            continue;
+        };
+
+        let current = bullet.direction.normalize_or_zero();
+        let desired = (target_transform.translation() - position).normalize_or_zero();
+        if current == Vec3::ZERO || desired == Vec3::ZERO {
+            continue;
+        }
+
+        let angle = current.angle_between(desired);
+        let max_turn = homing.turn_rate * time.delta_seconds();
+        bullet.direction = if angle <= max_turn {
+            desired
+        } else {
+            let turn =
+                Quat::IDENTITY.slerp(Quat::from_rotation_arc(current, desired), max_turn / angle);
+            turn * current
+        };
+
+        let facing = transform.translation + bullet.direction;
+        transform.look_at(facing, Vec3::Y);
     }
 }
 
@@ -42,7 +188,65 @@ fn bullet_despawn(
     for (entity, mut lifetime) in &mut bullets {
         lifetime.timer.tick(time.delta());
         if lifetime.timer.just_finished() {
-            commands.entity(entity).despawn_recursive();
+            commands.entity(entity).insert(Released);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::headless_app;
+    use crate::{GridEntry, Movement};
+
+    #[test]
+    fn homing_bullets_only_retarget_what_their_tower_can_shoot() {
+        let mut app = headless_app();
+        app.init_resource::<TargetGrid>().add_system(bullet_homing);
+        let mut spawn_target = |position: Vec3, movement: Movement| {
+            let entity = app
+                .world
+                .spawn((
+                    Target {
+                        movement,
+                        ..default()
+                    },
+                    GlobalTransform::from_translation(position),
+                ))
+                .id();
+            app.world.resource_mut::<TargetGrid>().insert(GridEntry {
+                entity,
+                position,
+                movement,
+            });
+            entity
+        };
+        // The walker is closer, but an anti-air bullet has to ignore it
+        spawn_target(Vec3::X, Movement::Ground);
+        let flyer = spawn_target(Vec3::X * 2.0, Movement::Flying);
+        let bullet = app
+            .world
+            .spawn((
+                Bullet {
+                    direction: Vec3::X,
+                    ..default()
+                },
+                Homing {
+                    target: None,
+                    turn_rate: 4.0,
+                    retarget_range: 3.0,
+                    can_target: CanTarget {
+                        ground: false,
+                        air: true,
+                    },
+                },
+                Transform::default(),
+                GlobalTransform::default(),
+            ))
+            .id();
+
+        app.update();
+
+        assert_eq!(app.world.get::<Homing>(bullet).unwrap().target, Some(flyer));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{DamageEvent, DamageKind, GameState, PortraitCamera};
+use bevy::prelude::*;
+
+/// Seconds a damage number stays on screen.
+const DAMAGE_NUMBER_LIFETIME: f32 = 0.8;
+/// World units per second the numbers float upwards.
+const RISE_SPEED: f32 = 1.2;
+const FONT_SIZE: f32 = 18.0;
+const CRITICAL_FONT_SIZE: f32 = 30.0;
+
+#[derive(Resource)]
+pub struct CombatTextSettings {
+    /// Add rapid hits on the same target into one number instead of stacking up new ones
+    pub aggregate: bool,
+    /// How long after appearing a number keeps absorbing hits when aggregating
+    pub aggregate_window: f32,
+}
+
+impl Default for CombatTextSettings {
+    fn default() -> Self {
+        CombatTextSettings {
+            aggregate: true,
+            aggregate_window: 0.25,
+        }
+    }
+}
+
+/// A number floating up from where a target was hit, drawn as UI text over the world.
+#[derive(Component)]
+pub struct DamageNumber {
+    target: Entity,
+    kind: DamageKind,
+    critical: bool,
+    amount: f32,
+    world_position: Vec3,
+    timer: Timer,
+}
+
+#[derive(Resource)]
+pub struct CombatTextAssets {
+    font: Handle<Font>,
+}
+
+pub struct CombatTextPlugin;
+
+impl Plugin for CombatTextPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CombatTextSettings>()
+            .add_startup_system(combat_text_asset_loading)
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(spawn_damage_numbers)
+                    .with_system(float_damage_numbers.after(spawn_damage_numbers)),
+            );
+    }
+}
+
+fn combat_text_asset_loading(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CombatTextAssets {
+        font: asset_server.load("fonts/Merriweather-Regular.ttf"),
+    });
+}
+
+fn damage_color(kind: DamageKind) -> Color {
+    match kind {
+        DamageKind::Physical => Color::WHITE,
+        DamageKind::Explosive => Color::rgb(1.0, 0.6, 0.1),
+        DamageKind::Lightning => Color::rgb(0.5, 0.8, 1.0),
+        DamageKind::Laser => Color::rgb(1.0, 0.3, 0.3),
+    }
+}
+
+fn damage_text(amount: f32, critical: bool) -> String {
+    if critical {
+        format!("{:.1}!", amount)
+    } else {
+        format!("{:.1}", amount)
+    }
+}
+
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    assets: Res<CombatTextAssets>,
+    settings: Res<CombatTextSettings>,
+    targets: Query<&GlobalTransform>,
+    mut numbers: Query<(&mut DamageNumber, &mut Text)>,
+    mut damage_events: EventReader<DamageEvent>,
+) {
+    // Hits this frame, merged up front when aggregating since new numbers only exist next frame
+    let mut hits: Vec<DamageNumber> = Vec::new();
+    for damage in damage_events.iter() {
+        let Ok(transform) = targets.get(damage.target) else {
+            continue;
+        };
+
+        // Crits always get a number of their own so they stand out
+        let mergeable = |number: &DamageNumber| {
+            settings.aggregate
+                && !damage.critical
+                && !number.critical
+                && number.target == damage.target
+                && number.kind == damage.kind
+                && number.timer.elapsed_secs() < settings.aggregate_window
+        };
+        if let Some(hit) = hits.iter_mut().find(|hit| mergeable(hit)) {
+            hit.amount += damage.amount;
+            continue;
+        }
+        if let Some((mut number, mut text)) =
+            numbers.iter_mut().find(|(number, _)| mergeable(number))
+        {
+            number.amount += damage.amount;
+            text.sections[0].value = damage_text(number.amount, false);
+            continue;
+        }
+
+        hits.push(DamageNumber {
+            target: damage.target,
+            kind: damage.kind,
+            critical: damage.critical,
+            amount: damage.amount,
+            world_position: transform.translation() + Vec3::Y,
+            timer: Timer::from_seconds(DAMAGE_NUMBER_LIFETIME, TimerMode::Once),
+        });
+    }
+
+    for hit in hits {
+        let font_size = if hit.critical {
+            CRITICAL_FONT_SIZE
+        } else {
+            FONT_SIZE
+        };
+        commands
+            .spawn(
+                TextBundle::from_section(
+                    damage_text(hit.amount, hit.critical),
+                    TextStyle {
+                        font: assets.font.clone(),
+                        font_size,
+                        color: damage_color(hit.kind),
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                }),
+            )
+            // Placed by float_damage_numbers once it knows where the hit is on screen
+            .insert(Visibility::INVISIBLE)
+            .insert(hit)
+            .insert(Name::new("Damage Number"));
+    }
+}
+
+fn float_damage_numbers(
+    mut commands: Commands,
+    mut numbers: Query<(
+        Entity,
+        &mut DamageNumber,
+        &mut Text,
+        &mut Style,
+        &mut Visibility,
+    )>,
+    camera: Query<(&Camera, &GlobalTransform), (With<Camera3d>, Without<PortraitCamera>)>,
+    time: Res<Time>,
+) {
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+
+    for (entity, mut number, mut text, mut style, mut visibility) in &mut numbers {
+        number.timer.tick(time.delta());
+        if number.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        number.world_position.y += RISE_SPEED * time.delta_seconds();
+        let alpha = 1.0 - number.timer.percent();
+        text.sections[0].style.color.set_a(alpha);
+
+        // Viewport coordinates start at the bottom left, like UI positions measured from the bottom
+        match camera.world_to_viewport(camera_transform, number.world_position) {
+            Some(screen_position) => {
+                style.position.left = Val::Px(screen_position.x);
+                style.position.bottom = Val::Px(screen_position.y);
+                visibility.is_visible = true;
+            }
+            None => visibility.is_visible = false,
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{GameAssets, GameState, TargetDeathEvent};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use std::fmt;
+
+/// Seconds a dropped crystal floats above where its enemy died.
+const LOOT_DROP_LIFETIME: f32 = 1.2;
+
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Currency {
+    Gold,
+    /// Rare, only dropped by special enemies
+    Crystals,
+}
+
+impl Currency {
+    pub const ALL: [Currency; 2] = [Currency::Gold, Currency::Crystals];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Currency::Gold => "gold",
+            Currency::Crystals => "crystals",
+        }
+    }
+}
+
+/// An amount of each currency, both what the player holds and what things cost.
+#[derive(Reflect, Clone, Default, Debug)]
+pub struct Funds(HashMap<Currency, u32>);
+
+impl Funds {
+    pub fn new(amounts: &[(Currency, u32)]) -> Self {
+        Funds(amounts.iter().copied().collect())
+    }
+
+    pub fn gold(amount: u32) -> Self {
+        Funds::new(&[(Currency::Gold, amount)])
+    }
+
+    pub fn get(&self, currency: Currency) -> u32 {
+        self.0.get(&currency).copied().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, currency: Currency, amount: u32) {
+        *self.0.entry(currency).or_insert(0) += amount;
+    }
+
+    pub fn deposit(&mut self, other: &Funds) {
+        for (currency, amount) in &other.0 {
+            self.add(*currency, *amount);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.values().all(|amount| *amount == 0)
+    }
+
+    pub fn can_afford(&self, cost: &Funds) -> bool {
+        cost.0
+            .iter()
+            .all(|(currency, amount)| self.get(*currency) >= *amount)
+    }
+
+    /// Takes the cost out if there is enough of every currency, leaving the funds untouched if not.
+    pub fn spend(&mut self, cost: &Funds) -> bool {
+        if !self.can_afford(cost) {
+            return false;
+        }
+        for (currency, amount) in &cost.0 {
+            *self.0.entry(*currency).or_insert(0) -= amount;
+        }
+        true
+    }
+
+    pub fn scaled(&self, factor: u32) -> Funds {
+        Funds(
+            self.0
+                .iter()
+                .map(|(currency, amount)| (*currency, amount * factor))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for Funds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = Currency::ALL
+            .iter()
+            .filter(|currency| self.get(**currency) > 0)
+            .map(|currency| format!("{} {}", self.get(*currency), currency.name()))
+            .collect();
+        match parts.is_empty() {
+            true => write!(f, "free"),
+            false => write!(f, "{}", parts.join(" + ")),
+        }
+    }
+}
+
+/// Crystal left floating where a special enemy died, already paid out.
+#[derive(Component)]
+pub struct LootDrop {
+    timer: Timer,
+}
+
+pub struct CurrencyPlugin;
+
+impl Plugin for CurrencyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Funds>().add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(spawn_loot_drops)
+                .with_system(animate_loot_drops),
+        );
+    }
+}
+
+fn spawn_loot_drops(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut death_events: EventReader<TargetDeathEvent>,
+) {
+    for death in death_events.iter() {
+        if death.enemy_type.loot().get(Currency::Crystals) == 0 {
+            continue;
+        }
+        commands
+            .spawn(SceneBundle {
+                scene: assets.crystal_scene.clone(),
+                transform: Transform::from_translation(death.position),
+                ..default()
+            })
+            .insert(LootDrop {
+                timer: Timer::from_seconds(LOOT_DROP_LIFETIME, TimerMode::Once),
+            })
+            .insert(Name::new("Loot Drop"));
+    }
+}
+
+fn animate_loot_drops(
+    mut commands: Commands,
+    mut drops: Query<(Entity, &mut LootDrop, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut drop, mut transform) in &mut drops {
+        drop.timer.tick(time.delta());
+        transform.translation.y += time.delta_seconds();
+        transform.rotate_y(4.0 * time.delta_seconds());
+        // Shrinks away as it is collected
+        transform.scale = Vec3::splat(1.0 - drop.timer.percent());
+
+        if drop.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{Currency, GameState, Level, Player, WaveClearedEvent};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Seconds the income breakdown stays up after a wave is cleared.
+const TOAST_DURATION: f32 = 4.0;
+
+/// Gold income rates, read from the level file so saving and spending can be tuned.
+#[derive(Deserialize, Clone)]
+pub struct EconomySettings {
+    pub starting_gold: u32,
+    /// Paid for every wave cleared
+    pub wave_bonus: u32,
+    /// Added to the wave bonus for each wave number, so later waves pay more
+    pub wave_bonus_per_wave: u32,
+    /// Fraction of banked gold paid as interest when a wave is cleared
+    pub interest_rate: f32,
+    /// Most interest a single wave can pay
+    pub interest_cap: u32,
+    /// Paid for each second of countdown skipped by calling a wave early
+    pub call_early_bonus_per_second: f32,
+}
+
+impl EconomySettings {
+    pub fn wave_bonus(&self, wave: usize) -> u32 {
+        self.wave_bonus + self.wave_bonus_per_wave * wave as u32
+    }
+
+    pub fn interest(&self, banked: u32) -> u32 {
+        ((banked as f32 * self.interest_rate).floor() as u32).min(self.interest_cap)
+    }
+}
+
+/// Breakdown of what a cleared wave paid, fading out after a few seconds.
+#[derive(Component)]
+pub struct IncomeToast {
+    timer: Timer,
+}
+
+pub struct EconomyPlugin;
+
+impl Plugin for EconomyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(pay_wave_income)
+                .with_system(fade_income_toasts),
+        );
+    }
+}
+
+fn pay_wave_income(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level: Res<Level>,
+    mut player: Query<&mut Player>,
+    mut cleared_events: EventReader<WaveClearedEvent>,
+    toasts: Query<Entity, With<IncomeToast>>,
+) {
+    let economy = &level.economy;
+    let mut lines = Vec::new();
+    for event in cleared_events.iter() {
+        let mut player = player.single_mut();
+        // Interest is on what was banked before this wave's bonus comes in
+        let banked = player.funds.get(Currency::Gold);
+        let interest = economy.interest(banked);
+        let bonus = economy.wave_bonus(event.wave);
+        player.funds.add(Currency::Gold, bonus + interest);
+
+        lines.push(format!("Wave {} cleared", event.wave));
+        lines.push(format!("Completion bonus +{}", bonus));
+        lines.push(format!(
+            "Interest +{} ({:.0}% of {}, max {})",
+            interest,
+            economy.interest_rate * 100.0,
+            banked,
+            economy.interest_cap
+        ));
+        lines.push(format!("Total +{}", bonus + interest));
+    }
+    if lines.is_empty() {
+        return;
+    }
+
+    // Only the latest breakdown is shown
+    for toast in &toasts {
+        commands.entity(toast).despawn_recursive();
+    }
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    // Below the ability bar
+                    top: Val::Percent(20.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(IncomeToast {
+            timer: Timer::from_seconds(TOAST_DURATION, TimerMode::Once),
+        })
+        .insert(Name::new("Income Toast"))
+        .with_children(|commands| {
+            // Text has no background of its own, so it sits on a backing panel
+            commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                    ..default()
+                })
+                .with_children(|commands| {
+                    commands.spawn(
+                        TextBundle::from_section(
+                            lines.join("\n"),
+                            TextStyle {
+                                font: asset_server.load("fonts/Merriweather-Regular.ttf"),
+                                font_size: 28.0,
+                                color: Color::GOLD,
+                            },
+                        )
+                        .with_text_alignment(TextAlignment::CENTER),
+                    );
+                });
+        });
+}
+
+fn fade_income_toasts(
+    mut commands: Commands,
+    mut toasts: Query<(Entity, &mut IncomeToast, &Children)>,
+    mut panels: Query<(&mut BackgroundColor, &Children), Without<IncomeToast>>,
+    mut texts: Query<&mut Text>,
+    time: Res<Time>,
+) {
+    for (entity, mut toast, children) in &mut toasts {
+        // Real time so it reads the same at any game speed
+        toast.timer.tick(time.raw_delta());
+        if toast.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        // Fades over the last second
+        let alpha = toast.timer.remaining_secs().min(1.0);
+        for &child in children.iter() {
+            let Ok((mut background, panel_children)) = panels.get_mut(child) else {
+                continue;
+            };
+            background.0.set_a(0.6 * alpha);
+            for &text in panel_children.iter() {
+                if let Ok(mut text) = texts.get_mut(text) {
+                    text.sections[0].style.color.set_a(alpha);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{EnemyType, GameState, Health, Shield, StatusEffects, Target};
+use bevy::prelude::*;
+use bevy_mod_picking::Selection;
+
+/// Panel describing the selected enemy, closed when it is deselected or dies.
+#[derive(Component)]
+pub struct EnemyPanelRoot {
+    target: Entity,
+}
+
+#[derive(Component)]
+pub struct EnemyPanelText;
+
+pub struct EnemyPanelPlugin;
+
+impl Plugin for EnemyPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(enemy_panel_on_selection)
+                .with_system(update_enemy_panel.after(enemy_panel_on_selection)),
+        );
+    }
+}
+
+fn enemy_panel_on_selection(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    targets: Query<(Entity, &Selection), With<Target>>,
+    panels: Query<(Entity, &EnemyPanelRoot)>,
+) {
+    let selected = targets
+        .iter()
+        .find(|(_, selection)| selection.selected())
+        .map(|(entity, _)| entity);
+
+    let mut up_to_date = false;
+    for (panel_entity, panel) in &panels {
+        if selected == Some(panel.target) {
+            up_to_date = true;
+        } else {
+            commands.entity(panel_entity).despawn_recursive();
+        }
+    }
+
+    if up_to_date {
+        return;
+    }
+    if let Some(target) = selected {
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Percent(1.0),
+                        // Below the wave preview
+                        top: Val::Percent(42.0),
+                        ..default()
+                    },
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            })
+            .insert(EnemyPanelRoot { target })
+            .insert(Name::new("Enemy Panel"))
+            .with_children(|commands| {
+                commands
+                    .spawn(TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/Merriweather-Regular.ttf"),
+                            font_size: 24.0,
+                            color: Color::WHITE,
+                        },
+                    ))
+                    .insert(EnemyPanelText);
+            });
+    }
+}
+
+fn update_enemy_panel(
+    panel: Query<&EnemyPanelRoot>,
+    targets: Query<(
+        &EnemyType,
+        &Target,
+        &Health,
+        Option<&Shield>,
+        &StatusEffects,
+    )>,
+    mut text: Query<&mut Text, With<EnemyPanelText>>,
+) {
+    let (Ok(panel), Ok(mut text)) = (panel.get_single(), text.get_single_mut()) else {
+        return;
+    };
+    // Gone for the frame between dying and the panel closing
+    let Ok((enemy_type, target, health, shield, effects)) = targets.get(panel.target) else {
+        return;
+    };
+
+    let mut lines = vec![
+        enemy_type.name().to_string(),
+        format!("Health {:.1} / {:.1}", health.value.max(0.0), health.max),
+    ];
+    if let Some(shield) = shield {
+        lines.push(format!("Shield {:.1} / {:.1}", shield.value, shield.max));
+    }
+    lines.push(format!("Speed {:.2}", target.current_speed(effects)));
+    for effect in &effects.effects {
+        lines.push(format!("{} ({:.1}s)", effect.name, effect.remaining));
+    }
+    lines.push(format!("Bounty {}", enemy_type.bounty()));
+    let loot = enemy_type.loot();
+    if !loot.is_empty() {
+        lines.push(format!("Drops {}", loot));
+    }
+    lines.push(format!("Leak damage {}", enemy_type.leak_damage()));
+
+    text.sections[0].value = lines.join("\n");
+}
@@ -0,0 +1,218 @@
+use crate::{
+    Bullet, CanTarget, DamageEvent, DamageKind, GameState, PoolKind, ProjectilePool, Released,
+    SpentBullets, TargetGrid,
+};
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+/// Makes a bullet blow up on impact, damaging every target within `radius`.
+#[derive(Component, Clone, Copy)]
+pub struct Explosive {
+    pub radius: f32,
+    /// Damage dealt at the centre of the blast
+    pub damage: f32,
+    /// Fraction of the damage lost by the edge of the blast, 0 for none and 1 for all of it
+    pub falloff: f32,
+    /// Blow up on reaching this point even if nothing was hit on the way
+    pub detonation_point: Option<Vec3>,
+    /// What the blast can hurt, so ground-only shells leave flyers overhead alone
+    pub can_target: CanTarget,
+}
+
+impl Explosive {
+    pub fn damage_at(&self, distance: f32) -> f32 {
+        let edge = (distance / self.radius).clamp(0.0, 1.0);
+        self.damage * (1.0 - self.falloff * edge)
+    }
+}
+
+pub struct ExplosionEvent {
+    pub position: Vec3,
+    pub explosive: Explosive,
+    pub source: Option<Entity>,
+}
+
+#[derive(Component)]
+pub struct ExplosionEffect {
+    pub timer: Timer,
+    pub radius: f32,
+}
+
+#[derive(Resource)]
+pub struct ExplosionAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+pub struct ExplosionPlugin;
+
+impl Plugin for ExplosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ExplosionEvent>()
+            .add_startup_system(explosion_asset_loading)
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(detonate_at_point)
+                    .with_system(explosion_damage)
+                    .with_system(spawn_explosion_effects)
+                    .with_system(animate_explosion_effects),
+            );
+    }
+}
+
+fn explosion_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ExplosionAssets {
+        mesh: meshes.add(Mesh::from(shape::Icosphere {
+            radius: 1.0,
+            subdivisions: 3,
+        })),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 0.5, 0.1, 0.6),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+pub fn detonate_at_point(
+    mut commands: Commands,
+    bullets: Query<(Entity, &Bullet, &Explosive, &GlobalTransform), Without<Released>>,
+    mut spent: ResMut<SpentBullets>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+) {
+    for (entity, bullet, explosive, transform) in &bullets {
+        // Already blown up by hitting something this frame
+        if spent.0.contains(&entity) {
+            continue;
+        }
+        if let Some(point) = explosive.detonation_point {
+            // Detonate once the point is behind the bullet
+            if bullet.direction.dot(point - transform.translation()) <= 0.0 {
+                commands.entity(entity).insert(Released);
+                spent.0.push(entity);
+                explosion_events.send(ExplosionEvent {
+                    position: point,
+                    explosive: *explosive,
+                    source: bullet.source,
+                });
+            }
+        }
+    }
+}
+
+fn explosion_damage(
+    grid: Res<TargetGrid>,
+    mut explosion_events: EventReader<ExplosionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for explosion in explosion_events.iter() {
+        let can_target = explosion.explosive.can_target;
+        for entry in grid
+            .within(explosion.position, explosion.explosive.radius)
+            .filter(|entry| can_target.allows(entry.movement))
+        {
+            let distance = entry.position.distance(explosion.position);
+            damage_events.send(DamageEvent {
+                target: entry.entity,
+                amount: explosion.explosive.damage_at(distance),
+                kind: DamageKind::Explosive,
+                critical: false,
+                source: explosion.source,
+            });
+        }
+    }
+}
+
+fn spawn_explosion_effects(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    assets: Res<ExplosionAssets>,
+    mut explosion_events: EventReader<ExplosionEvent>,
+) {
+    let kind = PoolKind::Mesh(assets.mesh.clone(), assets.material.clone());
+    for explosion in explosion_events.iter() {
+        let transform = Transform::from_translation(explosion.position).with_scale(Vec3::ZERO);
+        let effect = pool.acquire(&mut commands, &kind, transform);
+        commands
+            .entity(effect)
+            .insert(ExplosionEffect {
+                timer: Timer::from_seconds(0.4, TimerMode::Once),
+                radius: explosion.explosive.radius,
+            })
+            .insert(Name::new("Explosion"));
+    }
+}
+
+fn animate_explosion_effects(
+    mut commands: Commands,
+    mut effects: Query<(Entity, &mut ExplosionEffect, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut effect, mut transform) in &mut effects {
+        effect.timer.tick(time.delta());
+        // Swell out to the blast radius then shrink away
+        let scale = effect.radius * (effect.timer.percent() * PI).sin();
+        transform.scale = Vec3::splat(scale);
+        if effect.timer.finished() {
+            commands.entity(entity).insert(Released);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{drain_events, headless_app, send_event};
+    use crate::{GridEntry, Movement};
+
+    #[test]
+    fn ground_only_blasts_leave_flyers_alone() {
+        let mut app = headless_app();
+        app.init_resource::<TargetGrid>()
+            .add_event::<ExplosionEvent>()
+            .add_event::<DamageEvent>()
+            .add_system(explosion_damage);
+        let mut grid = app.world.resource_mut::<TargetGrid>();
+        let walker = Entity::from_raw(0);
+        for (entity, movement) in [
+            (walker, Movement::Ground),
+            (Entity::from_raw(1), Movement::Flying),
+        ] {
+            grid.insert(GridEntry {
+                entity,
+                position: Vec3::X * 0.5,
+                movement,
+            });
+        }
+
+        send_event(
+            &mut app,
+            ExplosionEvent {
+                position: Vec3::ZERO,
+                explosive: Explosive {
+                    radius: 2.0,
+                    damage: 3.0,
+                    falloff: 0.5,
+                    detonation_point: None,
+                    can_target: CanTarget {
+                        ground: true,
+                        air: false,
+                    },
+                },
+                source: None,
+            },
+        );
+        app.update();
+
+        let damaged: Vec<Entity> = drain_events::<DamageEvent>(&mut app)
+            .iter()
+            .map(|hit| hit.target)
+            .collect();
+        assert_eq!(damaged, [walker]);
+    }
+}
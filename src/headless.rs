@@ -0,0 +1,18 @@
+use bevy::ecs::event::Event;
+use bevy::prelude::*;
+
+/// An app with no window, renderer or physics, for driving gameplay systems in tests.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app
+}
+
+pub fn send_event<E: Event>(app: &mut App, event: E) {
+    app.world.resource_mut::<Events<E>>().send(event);
+}
+
+/// Takes every `E` sent since the last drain, drain after each update so none are dropped.
+pub fn drain_events<E: Event>(app: &mut App) -> Vec<E> {
+    app.world.resource_mut::<Events<E>>().drain().collect()
+}
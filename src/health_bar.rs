@@ -0,0 +1,158 @@
+use crate::{GameState, Health, PortraitCamera, Shield};
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+
+const BAR_WIDTH: f32 = 1.0;
+const BAR_HEIGHT: f32 = 0.12;
+/// How far above the target's origin the bar floats.
+const BAR_OFFSET: f32 = 1.3;
+/// World size of the bar per unit of distance from the camera, keeping it the same size on screen.
+const SCALE_PER_DISTANCE: f32 = 0.08;
+
+/// Billboard above a target showing its health, with its shield layered on top.
+#[derive(Component)]
+pub struct HealthBar {
+    health_fill: Entity,
+    shield_fill: Entity,
+}
+
+#[derive(Component)]
+pub struct HealthBarFill;
+
+type FillQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Transform, &'static mut Visibility),
+    (With<HealthBarFill>, Without<HealthBar>),
+>;
+
+#[derive(Resource)]
+pub struct HealthBarAssets {
+    mesh: Handle<Mesh>,
+    background_material: Handle<StandardMaterial>,
+    health_material: Handle<StandardMaterial>,
+    shield_material: Handle<StandardMaterial>,
+}
+
+pub struct HealthBarPlugin;
+
+impl Plugin for HealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(health_bar_asset_loading)
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(spawn_health_bars)
+                    .with_system(update_health_bars),
+            );
+    }
+}
+
+fn health_bar_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut bar_material = |color: Color| {
+        materials.add(StandardMaterial {
+            base_color: color,
+            unlit: true,
+            ..default()
+        })
+    };
+    commands.insert_resource(HealthBarAssets {
+        mesh: meshes.add(Mesh::from(shape::Quad::new(Vec2::new(
+            BAR_WIDTH, BAR_HEIGHT,
+        )))),
+        background_material: bar_material(Color::rgb(0.1, 0.1, 0.1)),
+        health_material: bar_material(Color::rgb(0.8, 0.1, 0.1)),
+        shield_material: bar_material(Color::rgb(0.3, 0.6, 1.0)),
+    });
+}
+
+fn spawn_health_bars(
+    mut commands: Commands,
+    assets: Res<HealthBarAssets>,
+    targets: Query<Entity, Added<Health>>,
+) {
+    for target in &targets {
+        let mut fill = |material: &Handle<StandardMaterial>, depth: f32| {
+            commands
+                .spawn(PbrBundle {
+                    mesh: assets.mesh.clone(),
+                    material: material.clone(),
+                    // Nudged towards the camera so the layers don't flicker into each other
+                    transform: Transform::from_xyz(0.0, 0.0, depth),
+                    ..default()
+                })
+                .insert(NotShadowCaster)
+                .insert(HealthBarFill)
+                .id()
+        };
+        let background = fill(&assets.background_material, 0.0);
+        let health_fill = fill(&assets.health_material, 0.01);
+        let shield_fill = fill(&assets.shield_material, 0.02);
+
+        let bar = commands
+            .spawn(SpatialBundle {
+                transform: Transform::from_xyz(0.0, BAR_OFFSET, 0.0),
+                // Only shown once the target has taken damage
+                visibility: Visibility::INVISIBLE,
+                ..default()
+            })
+            .insert(HealthBar {
+                health_fill,
+                shield_fill,
+            })
+            .insert(Name::new("Health Bar"))
+            .push_children(&[background, health_fill, shield_fill])
+            .id();
+        commands.entity(target).add_child(bar);
+    }
+}
+
+fn update_health_bars(
+    mut bars: Query<(&HealthBar, &Parent, &mut Transform, &mut Visibility)>,
+    mut fills: FillQuery,
+    targets: Query<(&Health, Option<&Shield>, &GlobalTransform)>,
+    camera: Query<&GlobalTransform, (With<Camera3d>, Without<PortraitCamera>)>,
+) {
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    let (_, camera_rotation, camera_position) = camera.to_scale_rotation_translation();
+
+    for (bar, parent, mut transform, mut visibility) in &mut bars {
+        let Ok((health, shield, target_transform)) = targets.get(parent.get()) else {
+            continue;
+        };
+
+        let shield_fraction = shield.map(|shield| shield.value / shield.max);
+        let damaged =
+            health.value < health.max || shield_fraction.is_some_and(|fraction| fraction < 1.0);
+        visibility.is_visible = damaged;
+        if !damaged {
+            continue;
+        }
+
+        // Undo the target's own rotation so the bar always faces the camera
+        let (target_scale, target_rotation, target_position) =
+            target_transform.to_scale_rotation_translation();
+        let distance = camera_position.distance(target_position + Vec3::Y * BAR_OFFSET);
+        transform.rotation = target_rotation.inverse() * camera_rotation;
+        transform.scale = Vec3::splat(distance * SCALE_PER_DISTANCE) / target_scale;
+
+        set_fill(&mut fills, bar.health_fill, Some(health.value / health.max));
+        set_fill(&mut fills, bar.shield_fill, shield_fraction);
+    }
+}
+
+/// Shrinks a fill towards the left edge of the bar, hiding it when there's nothing to show.
+fn set_fill(fills: &mut FillQuery, fill: Entity, fraction: Option<f32>) {
+    let Ok((mut transform, mut visibility)) = fills.get_mut(fill) else {
+        return;
+    };
+    let fraction = fraction.unwrap_or(0.0).clamp(0.0, 1.0);
+    visibility.is_visible = fraction > 0.0;
+    transform.scale.x = fraction;
+    transform.translation.x = -BAR_WIDTH * (1.0 - fraction) / 2.0;
+}
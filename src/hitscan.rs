@@ -0,0 +1,249 @@
+use crate::{
+    has_line_of_sight, pick_target, AirPath, CanTarget, DamageEvent, DamageKind, GameState, Health,
+    Lifetime, LineOfSight, Movement, Obstacle, PoolKind, ProjectilePool, Target, TargetGrid,
+    TargetPath, TargetingMode, Tower,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Damage dealt instantly along a ray instead of by a travelling bullet.
+#[derive(Clone, Copy)]
+pub struct Hitscan {
+    pub damage: f32,
+    /// Chance from 0 to 1 of a shot being a critical hit
+    pub crit_chance: f32,
+    pub crit_multiplier: f32,
+}
+
+pub struct HitscanEvent {
+    pub tower: Entity,
+    pub origin: Vec3,
+    pub direction: Vec3,
+    pub range: f32,
+    pub hitscan: Hitscan,
+    /// Targets the tower can't shoot at don't stop the ray
+    pub can_target: CanTarget,
+}
+
+/// A continuous laser that locks onto a single target and burns it, hurting
+/// more the longer it manages to hold on.
+#[derive(Component)]
+pub struct Beam {
+    pub target: Option<Entity>,
+    /// Seconds the current target has been held
+    pub held_for: f32,
+    pub base_dps: f32,
+    /// Extra damage per second gained for every second on the same target
+    pub ramp_per_second: f32,
+    pub max_dps: f32,
+    pub visual: Option<Entity>,
+}
+
+impl Beam {
+    pub fn dps(&self) -> f32 {
+        (self.base_dps + self.ramp_per_second * self.held_for).min(self.max_dps)
+    }
+}
+
+#[derive(Component)]
+pub struct BeamVisual;
+
+#[derive(Resource)]
+pub struct HitscanAssets {
+    line_mesh: Handle<Mesh>,
+    tracer_material: Handle<StandardMaterial>,
+    beam_material: Handle<StandardMaterial>,
+}
+
+pub struct HitscanPlugin;
+
+impl Plugin for HitscanPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HitscanEvent>()
+            .add_startup_system(hitscan_asset_loading)
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(hitscan_shots)
+                    .with_system(beam_towers),
+            );
+    }
+}
+
+/// Places a unit cube so it stretches from `from` to `to`.
+pub fn line_between(from: Vec3, to: Vec3, thickness: f32) -> Transform {
+    Transform::from_translation(from.lerp(to, 0.5))
+        .looking_at(to, Vec3::Y)
+        .with_scale(Vec3::new(thickness, thickness, from.distance(to)))
+}
+
+fn hitscan_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(HitscanAssets {
+        line_mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+        tracer_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.9, 0.5),
+            unlit: true,
+            ..default()
+        }),
+        beam_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.2, 0.2),
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+fn hitscan_shots(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    assets: Res<HitscanAssets>,
+    rapier_context: Res<RapierContext>,
+    targets: Query<&Target>,
+    mut hitscan_events: EventReader<HitscanEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for shot in hitscan_events.iter() {
+        let direction = shot.direction.normalize_or_zero();
+        let can_hit = |entity| {
+            targets
+                .get(entity)
+                .is_ok_and(|target| shot.can_target.allows(target.movement))
+        };
+        let filter = QueryFilter::default().predicate(&can_hit);
+        // Whatever target is first along the line takes the hit, not necessarily the one aimed at
+        let end = match rapier_context.cast_ray(shot.origin, direction, shot.range, true, filter) {
+            Some((target, distance)) => {
+                let critical = rand::random::<f32>() < shot.hitscan.crit_chance;
+                let multiplier = if critical {
+                    shot.hitscan.crit_multiplier
+                } else {
+                    1.0
+                };
+                damage_events.send(DamageEvent {
+                    target,
+                    amount: shot.hitscan.damage * multiplier,
+                    kind: DamageKind::Laser,
+                    critical,
+                    source: Some(shot.tower),
+                });
+                shot.origin + direction * distance
+            }
+            None => shot.origin + direction * shot.range,
+        };
+
+        let kind = PoolKind::Mesh(assets.line_mesh.clone(), assets.tracer_material.clone());
+        let tracer = pool.acquire(&mut commands, &kind, line_between(shot.origin, end, 0.03));
+        commands
+            .entity(tracer)
+            .insert(Lifetime {
+                timer: Timer::from_seconds(0.08, TimerMode::Once),
+            })
+            .insert(Name::new("Tracer"));
+    }
+}
+
+fn beam_towers(
+    mut commands: Commands,
+    assets: Res<HitscanAssets>,
+    mut towers: Query<(
+        Entity,
+        &Tower,
+        &CanTarget,
+        &TargetingMode,
+        &GlobalTransform,
+        &mut Beam,
+        Option<&LineOfSight>,
+    )>,
+    targets: Query<(&GlobalTransform, &Target)>,
+    target_stats: Query<(&Target, &Health)>,
+    path: Res<TargetPath>,
+    air_path: Res<AirPath>,
+    grid: Res<TargetGrid>,
+    rapier_context: Res<RapierContext>,
+    obstacles: Query<(), With<Obstacle>>,
+    mut visuals: Query<(&mut Transform, &mut Visibility), With<BeamVisual>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (tower_entity, tower, can_target, targeting, transform, mut beam, line_of_sight) in
+        &mut towers
+    {
+        let origin = transform.translation() + tower.bullet_offset;
+        let in_reach = |movement: Movement, position: Vec3| {
+            can_target.allows(movement)
+                && position.distance(origin) < tower.range
+                && (line_of_sight.is_none()
+                    || has_line_of_sight(&rapier_context, &obstacles, origin, position))
+        };
+
+        // Hold on to the current target for as long as it stays in reach
+        let mut locked = beam.target.and_then(|entity| {
+            let (target_transform, target) = targets.get(entity).ok()?;
+            let position = target_transform.translation();
+            in_reach(target.movement, position).then_some((entity, position))
+        });
+        if locked.is_none() {
+            beam.held_for = 0.0;
+            let candidates = grid
+                .within(origin, tower.range)
+                .filter(|entry| in_reach(entry.movement, entry.position));
+            locked = pick_target(
+                *targeting,
+                origin,
+                candidates,
+                &target_stats,
+                &path,
+                &air_path,
+            )
+            .map(|entry| (entry.entity, entry.position));
+        }
+        beam.target = locked.map(|(target, _)| target);
+
+        let Some((target, target_position)) = locked else {
+            if let Some(visual) = beam.visual {
+                if let Ok((_, mut visibility)) = visuals.get_mut(visual) {
+                    visibility.is_visible = false;
+                }
+            }
+            continue;
+        };
+
+        beam.held_for += time.delta_seconds();
+        damage_events.send(DamageEvent {
+            target,
+            amount: beam.dps() * time.delta_seconds(),
+            kind: DamageKind::Laser,
+            critical: false,
+            source: Some(tower_entity),
+        });
+
+        // Thicken the beam as it ramps up
+        let thickness = 0.03 + 0.05 * beam.dps() / beam.max_dps;
+        let line = line_between(origin, target_position, thickness);
+        match beam.visual {
+            Some(visual) => {
+                // Missing for a frame while the spawn command is applied
+                if let Ok((mut visual_transform, mut visibility)) = visuals.get_mut(visual) {
+                    *visual_transform = line;
+                    visibility.is_visible = true;
+                }
+            }
+            None => {
+                let visual = commands
+                    .spawn(PbrBundle {
+                        mesh: assets.line_mesh.clone(),
+                        material: assets.beam_material.clone(),
+                        transform: line,
+                        ..default()
+                    })
+                    .insert(BeamVisual)
+                    .insert(Name::new("Beam"))
+                    .id();
+                beam.visual = Some(visual);
+            }
+        }
+    }
+}
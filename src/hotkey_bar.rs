@@ -0,0 +1,97 @@
+use crate::Funds;
+use bevy::prelude::*;
+
+/// Something bought from a row of buttons that can also be picked with a number key,
+/// also marks its button.
+pub trait HotkeyButton: Component + Copy + PartialEq {
+    const ALL: &'static [Self];
+
+    fn name(&self) -> &'static str;
+    fn hotkey(&self) -> KeyCode;
+    fn hotkey_label(&self) -> &'static str;
+    fn cost(&self) -> Funds;
+}
+
+/// The line under a button's name, starting out as its cost.
+#[derive(Component)]
+pub struct HotkeyStatusText<T: HotkeyButton> {
+    pub button: T,
+}
+
+/// Spawns a button for each of `T` inside a transparent row laid out by `style`.
+pub fn spawn_hotkey_bar<T: HotkeyButton>(
+    commands: &mut Commands,
+    font: Handle<Font>,
+    style: Style,
+) -> Entity {
+    commands
+        .spawn(NodeBundle {
+            style,
+            background_color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|commands| {
+            for &button in T::ALL {
+                commands
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::horizontal(Val::Px(4.0)),
+                            padding: UiRect::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(button)
+                    .with_children(|commands| {
+                        commands.spawn(TextBundle::from_section(
+                            format!("[{}] {}", button.hotkey_label(), button.name()),
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 20.0,
+                                color: Color::BLACK,
+                            },
+                        ));
+                        commands
+                            .spawn(TextBundle::from_section(
+                                button.cost().to_string(),
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 14.0,
+                                    color: Color::BLACK,
+                                },
+                            ))
+                            .insert(HotkeyStatusText { button });
+                    });
+            }
+        })
+        .id()
+}
+
+/// Buttons clicked or picked by their hotkey this frame.
+pub fn pressed_hotkey_buttons<T: HotkeyButton>(
+    interactions: &Query<(&Interaction, &T), Changed<Interaction>>,
+    keyboard: &Input<KeyCode>,
+) -> Vec<T> {
+    let clicked = interactions
+        .iter()
+        .filter(|(interaction, _)| matches!(interaction, Interaction::Clicked))
+        .map(|(_, button)| *button);
+    let pressed = T::ALL
+        .iter()
+        .copied()
+        .filter(|button| keyboard.just_pressed(button.hotkey()));
+    clicked.chain(pressed).collect()
+}
+
+/// Highlights the armed button and greys out the ones that can't be used yet.
+pub fn hotkey_button_tint(armed: bool, ready: bool) -> BackgroundColor {
+    if armed {
+        Color::YELLOW.into()
+    } else if ready {
+        Color::WHITE.into()
+    } else {
+        Color::GRAY.into()
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{Currency, Funds, GameState, Player, Target, Waves};
+use bevy::prelude::*;
+
+const HUD_FONT_SIZE: f32 = 28.0;
+/// Speeds the game speed button steps through.
+const GAME_SPEEDS: [f32; 3] = [1.0, 2.0, 3.0];
+/// Seconds a changed value stays highlighted.
+const FLASH_DURATION: f32 = 0.6;
+
+/// How fast the game runs, applied to `Time` so every system speeds up with it.
+#[derive(Resource)]
+pub struct GameSpeed(pub f32);
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        GameSpeed(GAME_SPEEDS[0])
+    }
+}
+
+impl GameSpeed {
+    pub fn next(&self) -> f32 {
+        GAME_SPEEDS
+            .iter()
+            .copied()
+            .find(|speed| *speed > self.0)
+            .unwrap_or(GAME_SPEEDS[0])
+    }
+}
+
+#[derive(Component)]
+pub struct HudRoot;
+
+/// Which value a piece of HUD text shows.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum HudStat {
+    Funds(Currency),
+    Lives,
+    Wave,
+    Score,
+    Enemies,
+    Speed,
+}
+
+#[derive(Component)]
+pub struct GameSpeedButton;
+
+/// Tints HUD text after its value changes, fading back to white.
+#[derive(Component)]
+pub struct HudFlash {
+    color: Color,
+    timer: Timer,
+}
+
+impl HudFlash {
+    fn new(color: Color) -> Self {
+        HudFlash {
+            color,
+            timer: Timer::from_seconds(FLASH_DURATION, TimerMode::Once),
+        }
+    }
+}
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameSpeed>()
+            .add_system_set(SystemSet::on_enter(GameState::Gameplay).with_system(spawn_hud))
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(update_player_stats)
+                    .with_system(update_wave_stats)
+                    .with_system(change_game_speed)
+                    .with_system(apply_game_speed.after(change_game_speed))
+                    .with_system(animate_flashes),
+            );
+    }
+}
+
+fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>, speed: Res<GameSpeed>) {
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/Merriweather-Regular.ttf"),
+        font_size: HUD_FONT_SIZE,
+        color: Color::WHITE,
+    };
+    // Icon, tint and the value shown next to it
+    let stats = [
+        (
+            "detail_crystal.png",
+            Color::GOLD,
+            HudStat::Funds(Currency::Gold),
+        ),
+        (
+            "detail_crystalLarge.png",
+            Color::WHITE,
+            HudStat::Funds(Currency::Crystals),
+        ),
+        ("tile_endRoundSpawn.png", Color::WHITE, HudStat::Lives),
+        ("tile_spawn.png", Color::WHITE, HudStat::Wave),
+        ("towerSquare_sampleE.png", Color::WHITE, HudStat::Score),
+        ("enemy_ufoPurple.png", Color::WHITE, HudStat::Enemies),
+    ];
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(0.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .insert(HudRoot)
+        .insert(Name::new("HUD"))
+        .with_children(|commands| {
+            for (icon, tint, stat) in stats {
+                commands
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            margin: UiRect::right(Val::Px(24.0)),
+                            ..default()
+                        },
+                        background_color: Color::NONE.into(),
+                        ..default()
+                    })
+                    .with_children(|commands| {
+                        commands.spawn(ImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(32.0), Val::Px(32.0)),
+                                margin: UiRect::right(Val::Px(6.0)),
+                                ..default()
+                            },
+                            image: asset_server
+                                .load(format!("tower-defense-kit-1/Side/{}", icon))
+                                .into(),
+                            background_color: tint.into(),
+                            ..default()
+                        });
+                        commands
+                            .spawn(TextBundle::from_section("", text_style.clone()))
+                            .insert(stat);
+                    });
+            }
+
+            commands
+                .spawn(ButtonBundle {
+                    style: Style {
+                        margin: UiRect::left(Val::Auto),
+                        padding: UiRect::new(
+                            Val::Px(10.0),
+                            Val::Px(10.0),
+                            Val::Px(2.0),
+                            Val::Px(2.0),
+                        ),
+                        ..default()
+                    },
+                    background_color: Color::rgba(1.0, 1.0, 1.0, 0.2).into(),
+                    ..default()
+                })
+                .insert(GameSpeedButton)
+                .with_children(|commands| {
+                    commands
+                        .spawn(TextBundle::from_section(
+                            format!("{}x", speed.0),
+                            text_style.clone(),
+                        ))
+                        .insert(HudStat::Speed);
+                });
+        });
+}
+
+/// Only touches the text when the value differs, so unchanged stats don't relayout.
+fn set_text(text: &mut Mut<Text>, value: String) {
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
+
+fn update_player_stats(
+    mut commands: Commands,
+    player: Query<&Player, Changed<Player>>,
+    mut texts: Query<(Entity, &HudStat, &mut Text)>,
+    mut previous: Local<Option<(Funds, u32)>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+
+    for (entity, stat, mut text) in &mut texts {
+        match stat {
+            HudStat::Funds(currency) => {
+                let amount = player.funds.get(*currency);
+                set_text(&mut text, amount.to_string());
+                let gained = previous
+                    .as_ref()
+                    .is_some_and(|(funds, _)| amount > funds.get(*currency));
+                if gained {
+                    commands.entity(entity).insert(HudFlash::new(Color::GOLD));
+                }
+            }
+            HudStat::Lives => {
+                set_text(&mut text, player.health.to_string());
+                let lost = previous
+                    .as_ref()
+                    .is_some_and(|(_, health)| player.health < *health);
+                if lost {
+                    commands.entity(entity).insert(HudFlash::new(Color::RED));
+                }
+            }
+            HudStat::Score => set_text(&mut text, format!("Score {}", player.score)),
+            _ => {}
+        }
+    }
+    *previous = Some((player.funds.clone(), player.health));
+}
+
+fn update_wave_stats(
+    waves: Res<Waves>,
+    targets: Query<(), With<Target>>,
+    spawned: Query<(), Added<HudStat>>,
+    mut texts: Query<(&HudStat, &mut Text)>,
+    mut previous: Local<Option<(usize, usize, usize)>>,
+) {
+    // Only the counts are shown, the wave countdown changes `Waves` every frame
+    let shown = (waves.current, waves.total(), targets.iter().count());
+    if spawned.is_empty() && *previous == Some(shown) {
+        return;
+    }
+    *previous = Some(shown);
+    let (current, total, enemies) = shown;
+
+    for (stat, mut text) in &mut texts {
+        match stat {
+            HudStat::Wave => set_text(&mut text, format!("Wave {}/{}", current, total)),
+            HudStat::Enemies => set_text(&mut text, enemies.to_string()),
+            _ => {}
+        }
+    }
+}
+
+fn change_game_speed(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<GameSpeedButton>)>,
+    keyboard: Res<Input<KeyCode>>,
+    mut speed: ResMut<GameSpeed>,
+) {
+    let clicked = interactions
+        .iter()
+        .any(|interaction| matches!(interaction, Interaction::Clicked));
+    if clicked || keyboard.just_pressed(KeyCode::Tab) {
+        speed.0 = speed.next();
+    }
+}
+
+fn apply_game_speed(
+    speed: Res<GameSpeed>,
+    mut time: ResMut<Time>,
+    mut texts: Query<(&HudStat, &mut Text)>,
+) {
+    if !speed.is_changed() {
+        return;
+    }
+    time.set_relative_speed(speed.0);
+    for (stat, mut text) in &mut texts {
+        if *stat == HudStat::Speed {
+            set_text(&mut text, format!("{}x", speed.0));
+        }
+    }
+}
+
+fn animate_flashes(
+    mut commands: Commands,
+    mut flashes: Query<(Entity, &mut HudFlash, &mut Text)>,
+    time: Res<Time>,
+) {
+    for (entity, mut flash, mut text) in &mut flashes {
+        // Real time so the flash doesn't speed up with the game
+        flash.timer.tick(time.raw_delta());
+        let fade = flash.timer.percent();
+        let color = Vec4::from(flash.color).lerp(Vec4::from(Color::WHITE), fade);
+        let style = &mut text.sections[0].style;
+        style.color = color.into();
+        // Pops up in size and settles back
+        style.font_size = HUD_FONT_SIZE * (1.0 + 0.3 * (1.0 - fade));
+
+        if flash.timer.finished() {
+            commands.entity(entity).remove::<HudFlash>();
+        }
+    }
+}
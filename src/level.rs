@@ -0,0 +1,33 @@
+use crate::EconomySettings;
+use bevy::asset::FileAssetIo;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Relative to the directory `assets` is found in.
+const LEVEL_PATH: &str = "assets/levels/level_1.ron";
+
+/// Designer tunable settings for a level, every value comes from the level file.
+#[derive(Resource, Deserialize)]
+pub struct Level {
+    pub economy: EconomySettings,
+}
+
+impl Level {
+    fn load() -> Self {
+        // Found the same way the asset server finds `assets`, so it doesn't
+        // depend on the working directory
+        let path = FileAssetIo::get_base_path().join(LEVEL_PATH);
+        let source = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("Couldn't read level file {}: {}", path.display(), err));
+        ron::from_str(&source)
+            .unwrap_or_else(|err| panic!("Invalid level file {}: {}", path.display(), err))
+    }
+}
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Level::load());
+    }
+}
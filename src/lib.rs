@@ -0,0 +1,253 @@
+mod abilities;
+mod artillery;
+mod barracks;
+mod bullet;
+mod combat_text;
+mod currency;
+mod economy;
+mod enemy_panel;
+mod explosion;
+#[cfg(test)]
+mod headless;
+mod health_bar;
+mod hitscan;
+mod hotkey_bar;
+mod hud;
+mod level;
+mod lightning;
+mod main_menu;
+mod path;
+mod path_items;
+mod physics;
+mod placement;
+mod player;
+mod pool;
+mod portrait;
+mod range_indicator;
+mod sight;
+mod spatial;
+mod status;
+mod target;
+mod tower;
+mod tower_panel;
+mod tower_stats;
+mod wave;
+mod wave_preview;
+
+pub use abilities::*;
+pub use artillery::*;
+pub use barracks::*;
+pub use bullet::*;
+pub use combat_text::*;
+pub use currency::*;
+pub use economy::*;
+pub use enemy_panel::*;
+pub use explosion::*;
+pub use health_bar::*;
+pub use hitscan::*;
+pub use hotkey_bar::*;
+pub use hud::*;
+pub use level::*;
+pub use lightning::*;
+pub use main_menu::*;
+pub use path::*;
+pub use path_items::*;
+pub use physics::*;
+pub use placement::*;
+pub use player::*;
+pub use pool::*;
+pub use portrait::*;
+pub use range_indicator::*;
+pub use sight::*;
+pub use spatial::*;
+pub use status::*;
+use std::fmt::Debug;
+use std::hash::Hash;
+pub use target::*;
+pub use tower::*;
+pub use tower_panel::*;
+pub use tower_stats::*;
+pub use wave::*;
+pub use wave_preview::*;
+
+use bevy::prelude::*;
+use bevy_mod_picking::*;
+
+pub const HEIGHT: f32 = 720.0;
+pub const WIDTH: f32 = 1000.0;
+
+#[derive(Resource)]
+pub struct GameAssets {
+    tower_base_scene: Handle<Scene>,
+    tower_a_scene: Handle<Scene>,
+    tower_b_scene: Handle<Scene>,
+    tower_c_scene: Handle<Scene>,
+    tower_d_scene: Handle<Scene>,
+    tower_e_scene: Handle<Scene>,
+    tower_f_scene: Handle<Scene>,
+    tower_g_scene: Handle<Scene>,
+    tower_h_scene: Handle<Scene>,
+    tower_i_scene: Handle<Scene>,
+    tower_a_icon: Handle<Image>,
+    tower_b_icon: Handle<Image>,
+    tower_c_icon: Handle<Image>,
+    tower_d_icon: Handle<Image>,
+    tower_e_icon: Handle<Image>,
+    tower_f_icon: Handle<Image>,
+    tower_g_icon: Handle<Image>,
+    tower_h_icon: Handle<Image>,
+    tower_i_icon: Handle<Image>,
+    tower_a_bullet_scene: Handle<Scene>,
+    tower_b_bullet_scene: Handle<Scene>,
+    tower_c_bullet_scene: Handle<Scene>,
+    tower_d_bullet_scene: Handle<Scene>,
+    target_scene: Handle<Scene>,
+    flying_target_scene: Handle<Scene>,
+    rocks_scene: Handle<Scene>,
+    tree_scene: Handle<Scene>,
+    crystal_scene: Handle<Scene>,
+    spikes_scene: Handle<Scene>,
+    barricade_scene: Handle<Scene>,
+    soldier_scene: Handle<Scene>,
+    /// Rendered from the model by `PortraitPlugin`, there is no sprite for it
+    barbarian_icon: Handle<Image>,
+    ufo_icon: Handle<Image>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum GameState {
+    MainMenu,
+    Gameplay,
+}
+
+pub fn camera_controls(
+    keyboard: Res<Input<KeyCode>>,
+    mut camera_query: Query<&mut Transform, (With<Camera3d>, Without<PortraitCamera>)>,
+    time: Res<Time>,
+) {
+    let mut camera = camera_query.single_mut();
+    let mut forward = camera.forward();
+    let left = camera.left();
+    forward.y = 0.0;
+    forward = forward.normalize();
+
+    let speed = 3.0;
+    let rotate_speed = 1.0;
+    if keyboard.pressed(KeyCode::W) {
+        camera.translation += forward * speed * time.delta_seconds();
+    }
+    if keyboard.pressed(KeyCode::S) {
+        camera.translation -= forward * speed * time.delta_seconds();
+    }
+    if keyboard.pressed(KeyCode::A) {
+        camera.translation += left * speed * time.delta_seconds();
+    }
+    if keyboard.pressed(KeyCode::D) {
+        camera.translation -= left * speed * time.delta_seconds();
+    }
+    if keyboard.pressed(KeyCode::Q) {
+        camera.rotate_axis(Vec3::Y, rotate_speed * time.delta_seconds());
+    }
+    if keyboard.pressed(KeyCode::E) {
+        camera.rotate_axis(Vec3::Y, -rotate_speed * time.delta_seconds());
+    }
+}
+
+pub fn asset_loading(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    commands.insert_resource(GameAssets {
+        tower_base_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerSquare_bottomA.glb#Scene0"),
+        tower_a_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerSquare_sampleA.glb#Scene0"),
+        tower_b_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerSquare_sampleB.glb#Scene0"),
+        tower_c_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerSquare_sampleC.glb#Scene0"),
+        tower_d_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerSquare_sampleD.glb#Scene0"),
+        tower_e_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerRound_sampleF.glb#Scene0"),
+        tower_f_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerRound_sampleD.glb#Scene0"),
+        tower_g_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/towerRound_sampleE.glb#Scene0"),
+        tower_h_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/weapon_catapult.glb#Scene0"),
+        tower_i_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/woodStructure_high.glb#Scene0"),
+        tower_a_icon: assets.load("tower-defense-kit-1/Side/towerSquare_sampleA.png"),
+        tower_b_icon: assets.load("tower-defense-kit-1/Side/towerSquare_sampleB.png"),
+        tower_c_icon: assets.load("tower-defense-kit-1/Side/towerSquare_sampleC.png"),
+        tower_d_icon: assets.load("tower-defense-kit-1/Side/towerSquare_sampleD.png"),
+        tower_e_icon: assets.load("tower-defense-kit-1/Side/towerRound_sampleF.png"),
+        tower_f_icon: assets.load("tower-defense-kit-1/Side/towerRound_sampleD.png"),
+        tower_g_icon: assets.load("tower-defense-kit-1/Side/towerRound_sampleE.png"),
+        tower_h_icon: assets.load("tower-defense-kit-1/Side/weapon_catapult.png"),
+        tower_i_icon: assets.load("tower-defense-kit-1/Side/woodStructure_high.png"),
+        tower_a_bullet_scene: assets
+            .load("kay-kit-dungeon-pack1.0/Models/gltf/arrow-flippedx.gltf.glb#Scene0"),
+        tower_b_bullet_scene: assets
+            .load("kay-kit-dungeon-pack1.0/Models/gltf/arrow-flippedx.gltf.glb#Scene0"),
+        tower_c_bullet_scene: assets
+            .load("kay-kit-dungeon-pack1.0/Models/gltf/arrow-flippedx.gltf.glb#Scene0"),
+        tower_d_bullet_scene: assets
+            .load("kay-kit-dungeon-pack1.0/Models/gltf/arrow-flippedx.gltf.glb#Scene0"),
+        target_scene: assets
+            .load("kay-kit-dungeon-pack1.0/Models/Characters/gltf/barbarian.glb#Scene0"),
+        flying_target_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/enemy_ufoRed.glb#Scene0"),
+        rocks_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/detail_rocksLarge.glb#Scene0"),
+        tree_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/detail_treeLarge.glb#Scene0"),
+        crystal_scene: assets
+            .load("tower-defense-kit-1/Models/GLTFformat/detail_crystal.glb#Scene0"),
+        spikes_scene: assets.load("kay-kit-dungeon-pack1.0/Models/gltf/tileSpikes.gltf.glb#Scene0"),
+        barricade_scene: assets.load("kay-kit-dungeon-pack1.0/Models/gltf/crate.gltf.glb#Scene0"),
+        soldier_scene: assets
+            .load("kay-kit-dungeon-pack1.0/Models/Characters/gltf/character_knight.gltf#Scene0"),
+        barbarian_icon: new_portrait_image(&mut images),
+        ufo_icon: assets.load("tower-defense-kit-1/Side/enemy_ufoRed.png"),
+    });
+}
+
+pub fn spawn_camera(mut commands: Commands) {
+    commands
+        .spawn(Camera3dBundle {
+            transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        })
+        .insert(PickingCameraBundle::default());
+}
+
+pub fn spawn_basic_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Plane { size: 50.0 })),
+            material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+            ..default()
+        })
+        // Only raycast against for aiming and placing, never hovered or selected itself
+        .insert(PickableMesh::default())
+        .insert(Ground)
+        .insert(Name::new("Ground"));
+    commands
+        .spawn(PointLightBundle {
+            point_light: PointLight {
+                intensity: 1500.0,
+                shadows_enabled: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(4.0, 8.0, 4.0),
+            ..default()
+        })
+        .insert(Name::new("Light"));
+}
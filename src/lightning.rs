@@ -0,0 +1,220 @@
+use crate::{
+    line_between, DamageEvent, DamageKind, GameState, Lifetime, PoolKind, ProjectilePool, Target,
+    TargetGrid,
+};
+use bevy::prelude::*;
+
+/// Instant damage that arcs from target to target instead of firing a bullet.
+#[derive(Clone, Copy)]
+pub struct ChainLightning {
+    pub damage: f32,
+    /// How many more targets the bolt can arc to after the first
+    pub jumps: u32,
+    /// How far the bolt can arc between two targets
+    pub jump_radius: f32,
+    /// Fraction of the damage lost on every jump
+    pub damage_falloff: f32,
+}
+
+pub struct ChainLightningEvent {
+    pub tower: Entity,
+    pub origin: Vec3,
+    pub target: Entity,
+    pub lightning: ChainLightning,
+}
+
+#[derive(Resource)]
+pub struct LightningAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+pub struct LightningPlugin;
+
+impl Plugin for LightningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ChainLightningEvent>()
+            .add_startup_system(lightning_asset_loading)
+            .add_system_set(SystemSet::on_update(GameState::Gameplay).with_system(chain_lightning));
+    }
+}
+
+fn lightning_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(LightningAssets {
+        mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.6, 0.8, 1.0),
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+fn chain_lightning(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    assets: Res<LightningAssets>,
+    targets: Query<&GlobalTransform, With<Target>>,
+    grid: Res<TargetGrid>,
+    mut lightning_events: EventReader<ChainLightningEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for event in lightning_events.iter() {
+        let Ok(first_transform) = targets.get(event.target) else {
+            continue;
+        };
+
+        let mut hit = Vec::new();
+        let mut current = (event.target, first_transform.translation());
+        let mut from = event.origin;
+        let mut damage = event.lightning.damage;
+        loop {
+            let (target, position) = current;
+            hit.push(target);
+            spawn_bolt(&mut commands, &mut pool, &assets, from, position);
+            damage_events.send(DamageEvent {
+                target,
+                amount: damage,
+                kind: DamageKind::Lightning,
+                critical: false,
+                source: Some(event.tower),
+            });
+
+            if hit.len() > event.lightning.jumps as usize {
+                break;
+            }
+
+            let next_target = grid.closest(position, event.lightning.jump_radius, |entry| {
+                !hit.contains(&entry.entity)
+            });
+
+            let Some(next_target) = next_target else {
+                break;
+            };
+            from = position;
+            current = (next_target.entity, next_target.position);
+            damage *= 1.0 - event.lightning.damage_falloff;
+        }
+    }
+}
+
+fn spawn_bolt(
+    commands: &mut Commands,
+    pool: &mut ProjectilePool,
+    assets: &LightningAssets,
+    from: Vec3,
+    to: Vec3,
+) {
+    let kind = PoolKind::Mesh(assets.mesh.clone(), assets.material.clone());
+    let bolt = pool.acquire(commands, &kind, line_between(from, to, 0.04));
+    commands
+        .entity(bolt)
+        .insert(Lifetime {
+            timer: Timer::from_seconds(0.15, TimerMode::Once),
+        })
+        .insert(Name::new("Lightning"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{drain_events, headless_app, send_event};
+    use crate::{GridEntry, Movement};
+
+    fn lightning_app() -> App {
+        let mut app = headless_app();
+        app.init_resource::<TargetGrid>()
+            .insert_resource(ProjectilePool::new(false))
+            .insert_resource(LightningAssets {
+                mesh: Handle::default(),
+                material: Handle::default(),
+            })
+            .add_event::<ChainLightningEvent>()
+            .add_event::<DamageEvent>()
+            .add_system(chain_lightning);
+        app
+    }
+
+    fn spawn_targets(app: &mut App, positions: &[Vec3]) -> Vec<Entity> {
+        positions
+            .iter()
+            .map(|&position| {
+                let entity = app
+                    .world
+                    .spawn((
+                        Target::default(),
+                        GlobalTransform::from_translation(position),
+                    ))
+                    .id();
+                app.world.resource_mut::<TargetGrid>().insert(GridEntry {
+                    entity,
+                    position,
+                    movement: Movement::Ground,
+                });
+                entity
+            })
+            .collect()
+    }
+
+    /// Fires one bolt at `first` and returns every target it damaged, in order.
+    fn strike(app: &mut App, first: Entity, jumps: u32, jump_radius: f32) -> Vec<Entity> {
+        let tower = app.world.spawn_empty().id();
+        send_event(
+            app,
+            ChainLightningEvent {
+                tower,
+                origin: Vec3::Y,
+                target: first,
+                lightning: ChainLightning {
+                    damage: 4.0,
+                    jumps,
+                    jump_radius,
+                    damage_falloff: 0.25,
+                },
+            },
+        );
+        app.update();
+        drain_events::<DamageEvent>(app)
+            .iter()
+            .map(|hit| hit.target)
+            .collect()
+    }
+
+    #[test]
+    fn chain_stops_at_its_jump_count() {
+        let mut app = lightning_app();
+        let positions: Vec<Vec3> = (0..6).map(|i| Vec3::X * i as f32).collect();
+        let targets = spawn_targets(&mut app, &positions);
+
+        let hit = strike(&mut app, targets[0], 2, 1.5);
+
+        // The first target plus one per jump
+        assert_eq!(hit, targets[..3]);
+    }
+
+    #[test]
+    fn chain_stops_when_nothing_is_in_range() {
+        let mut app = lightning_app();
+        let positions = [Vec3::ZERO, Vec3::X, Vec3::X * 5.0];
+        let targets = spawn_targets(&mut app, &positions);
+
+        let hit = strike(&mut app, targets[0], 5, 1.5);
+
+        assert_eq!(hit, targets[..2]);
+    }
+
+    #[test]
+    fn chain_never_hits_a_target_twice() {
+        let mut app = lightning_app();
+        // Two targets in reach of each other, the bolt can't bounce back and forth
+        let targets = spawn_targets(&mut app, &[Vec3::ZERO, Vec3::X]);
+
+        let hit = strike(&mut app, targets[0], 5, 1.5);
+
+        assert_eq!(hit, targets);
+    }
+}
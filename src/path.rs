@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use bevy::utils::FloatOrd;
+
+/// How many straight pieces each spline segment is split into for the arc-length table.
+const SAMPLES_PER_SEGMENT: usize = 32;
+
+/// A Catmull-Rom spline through a list of control points, where the y of each
+/// point is the height of the path so it can climb slopes and cross bridges.
+///
+/// Positions are looked up by distance along the path rather than by spline
+/// parameter so anything walking it moves at a constant speed.
+#[derive(Resource)]
+pub struct TargetPath {
+    /// Cumulative arc length paired with the sampled position, starting at zero.
+    samples: Vec<(f32, Vec3)>,
+}
+
+impl TargetPath {
+    pub fn from_control_points(control_points: &[Vec3]) -> Self {
+        assert!(
+            control_points.len() >= 2,
+            "A path needs at least two control points"
+        );
+
+        let mut samples = vec![(0.0, control_points[0])];
+        let mut length = 0.0;
+        for segment in 0..control_points.len() - 1 {
+            for step in 1..=SAMPLES_PER_SEGMENT {
+                let t = step as f32 / SAMPLES_PER_SEGMENT as f32;
+                let position = catmull_rom(control_points, segment, t);
+                let (_, previous) = samples[samples.len() - 1];
+                if position.distance(previous) > f32::EPSILON {
+                    length += position.distance(previous);
+                    samples.push((length, position));
+                }
+            }
+        }
+        // Lookups need at least one span to interpolate along
+        assert!(
+            samples.len() >= 2,
+            "A path needs some length, its control points can't all be the same"
+        );
+
+        Self { samples }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.samples[self.samples.len() - 1].0
+    }
+
+    /// World position at `distance` along the path. Negative distances continue
+    /// backwards from the start so targets can queue up before entering the path.
+    pub fn position_at(&self, distance: f32) -> Vec3 {
+        if distance <= 0.0 {
+            return self.samples[0].1 + self.direction_at(0.0) * distance;
+        }
+        if distance >= self.length() {
+            return self.samples[self.samples.len() - 1].1;
+        }
+
+        let ((start_distance, start), (end_distance, end)) = self.span_at(distance);
+        let t = (distance - start_distance) / (end_distance - start_distance);
+        start.lerp(end, t)
+    }
+
+    /// Distance along the path closest to `point`, and how far across the ground it is from there.
+    pub fn nearest(&self, point: Vec3) -> (f32, f32) {
+        let flat = Vec3::new(1.0, 0.0, 1.0);
+        self.samples
+            .iter()
+            .map(|(distance, position)| (*distance, ((*position - point) * flat).length()))
+            .min_by_key(|(_, away)| FloatOrd(*away))
+            .expect("A path always has samples")
+    }
+
+    /// Unit direction of travel at `distance` along the path.
+    pub fn direction_at(&self, distance: f32) -> Vec3 {
+        let ((_, start), (_, end)) = self.span_at(distance);
+        (end - start).normalize_or_zero()
+    }
+
+    /// The two samples either side of `distance`, clamped to the first and last span.
+    fn span_at(&self, distance: f32) -> ((f32, Vec3), (f32, Vec3)) {
+        let index = self
+            .samples
+            .partition_point(|(sample_distance, _)| *sample_distance < distance)
+            .clamp(1, self.samples.len() - 1);
+        (self.samples[index - 1], self.samples[index])
+    }
+}
+
+/// Uniform Catmull-Rom interpolation of `segment` (between control points
+/// `segment` and `segment + 1`), repeating the end points for the outer tangents.
+fn catmull_rom(points: &[Vec3], segment: usize, t: f32) -> Vec3 {
+    let p0 = points[segment.saturating_sub(1)];
+    let p1 = points[segment];
+    let p2 = points[segment + 1];
+    let p3 = points[(segment + 2).min(points.len() - 1)];
+
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1)
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// The route flying targets take: straight from the start of the ground path
+/// to its end at a fixed altitude, ignoring every bend along the way.
+#[derive(Resource, Deref)]
+pub struct AirPath(TargetPath);
+
+impl AirPath {
+    pub fn over(ground: &TargetPath, altitude: f32) -> Self {
+        let lift = Vec3::Y * altitude;
+        AirPath(TargetPath::from_control_points(&[
+            ground.position_at(0.0) + lift,
+            ground.position_at(ground.length()) + lift,
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "A path needs some length")]
+    fn path_with_no_length_is_rejected() {
+        TargetPath::from_control_points(&[Vec3::ONE, Vec3::ONE, Vec3::ONE]);
+    }
+
+    #[test]
+    fn shortest_path_can_be_walked() {
+        let path = TargetPath::from_control_points(&[Vec3::ZERO, Vec3::X]);
+        assert!((path.length() - 1.0).abs() < 1e-4);
+        assert_eq!(path.direction_at(0.5), Vec3::X);
+        assert_eq!(path.position_at(2.0), path.position_at(path.length()));
+    }
+}
@@ -0,0 +1,382 @@
+use crate::{
+    cancel_placement, hotkey_button_tint, pressed_hotkey_buttons, spawn_hotkey_bar, Armed,
+    ArmedPlacement, CursorGround, DamageEvent, DamageKind, Funds, GameAssets, GameState, Health,
+    HotkeyButton, Movement, PathBlocker, Player, StatusEffect, StatusEvent, Target, TargetPath,
+};
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+/// Furthest the cursor can be from the path and still snap an item onto it.
+const PATH_SNAP: f32 = 1.5;
+/// Items can't be stacked closer than this along the path.
+const ITEM_SPACING: f32 = 1.5;
+/// How far along the path either side of an item walkers are caught by it.
+const ITEM_REACH: f32 = 0.75;
+const SPIKE_DAMAGE: f32 = 1.0;
+const SPIKE_INTERVAL: f32 = 0.75;
+const TAR_SLOW: f32 = 0.4;
+const BARRICADE_HEALTH: f32 = 10.0;
+
+/// Things built on the path itself rather than on a tower base, also marks their button.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathItem {
+    SpikeTrap,
+    TarPit,
+    Barricade,
+}
+
+impl HotkeyButton for PathItem {
+    const ALL: &'static [PathItem] = &[PathItem::SpikeTrap, PathItem::TarPit, PathItem::Barricade];
+
+    fn name(&self) -> &'static str {
+        match self {
+            PathItem::SpikeTrap => "Spikes",
+            PathItem::TarPit => "Tar pit",
+            PathItem::Barricade => "Barricade",
+        }
+    }
+
+    fn hotkey(&self) -> KeyCode {
+        match self {
+            PathItem::SpikeTrap => KeyCode::Key4,
+            PathItem::TarPit => KeyCode::Key5,
+            PathItem::Barricade => KeyCode::Key6,
+        }
+    }
+
+    fn hotkey_label(&self) -> &'static str {
+        match self {
+            PathItem::SpikeTrap => "4",
+            PathItem::TarPit => "5",
+            PathItem::Barricade => "6",
+        }
+    }
+
+    fn cost(&self) -> Funds {
+        match self {
+            PathItem::SpikeTrap => Funds::gold(40),
+            PathItem::TarPit => Funds::gold(30),
+            PathItem::Barricade => Funds::gold(25),
+        }
+    }
+}
+
+/// Where along the `TargetPath` a placed item sits.
+#[derive(Component)]
+pub struct PlacedItem {
+    pub distance: f32,
+}
+
+/// Hurts every walker standing on it each time the timer comes round.
+#[derive(Component)]
+pub struct SpikeTrap {
+    timer: Timer,
+}
+
+#[derive(Component)]
+pub struct TarPit;
+
+#[derive(Component)]
+pub struct Barricade;
+
+#[derive(Resource)]
+pub struct PathItemAssets {
+    tar_mesh: Handle<Mesh>,
+    tar_material: Handle<StandardMaterial>,
+}
+
+#[derive(Component)]
+pub struct PathItemBarRoot;
+
+/// Shows where on the path the item being placed would go.
+#[derive(Component)]
+pub struct PlacementMarker;
+
+pub struct PathItemPlugin;
+
+impl Plugin for PathItemPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(path_item_asset_loading)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Gameplay)
+                    .with_system(spawn_path_item_bar)
+                    .with_system(spawn_placement_marker),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    // Before the click that picks an item could be taken as the spot
+                    .with_system(
+                        place_path_items
+                            .after(cancel_placement)
+                            .before(path_item_input),
+                    )
+                    .with_system(path_item_input)
+                    .with_system(update_path_item_bar)
+                    .with_system(spike_traps)
+                    .with_system(tar_pits)
+                    .with_system(destroy_barricades),
+            );
+    }
+}
+
+fn path_item_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(PathItemAssets {
+        tar_mesh: meshes.add(Mesh::from(shape::Circle::new(0.6))),
+        tar_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.08, 0.06, 0.05),
+            perceptual_roughness: 0.2,
+            ..default()
+        }),
+    });
+}
+
+fn spawn_path_item_bar(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/Merriweather-Regular.ttf");
+    let bar = spawn_hotkey_bar::<PathItem>(
+        &mut commands,
+        font,
+        Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                right: Val::Percent(1.0),
+                top: Val::Percent(7.0),
+                ..default()
+            },
+            ..default()
+        },
+    );
+    commands
+        .entity(bar)
+        .insert(PathItemBarRoot)
+        .insert(Name::new("Path Item Bar"));
+}
+
+fn spawn_placement_marker(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Torus {
+                radius: ITEM_REACH,
+                ring_radius: 0.04,
+                subdivisions_segments: 32,
+                subdivisions_sides: 6,
+            })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::INVISIBLE,
+            ..default()
+        })
+        .insert(NotShadowCaster)
+        .insert(PlacementMarker)
+        .insert(Name::new("Placement Marker"));
+}
+
+fn path_item_input(
+    interactions: Query<(&Interaction, &PathItem), Changed<Interaction>>,
+    keyboard: Res<Input<KeyCode>>,
+    mut placement: ResMut<ArmedPlacement>,
+    player: Query<&Player>,
+) {
+    let player = player.single();
+    for item in pressed_hotkey_buttons(&interactions, &keyboard) {
+        // Can always be put away, only picked up while affordable
+        if placement.path_item() == Some(item) || player.funds.can_afford(&item.cost()) {
+            placement.toggle(Armed::PathItem(item));
+        }
+    }
+}
+
+/// Snaps the cursor onto the path, `None` if it is too far off or too close to another item.
+fn placement_spot(point: Vec3, path: &TargetPath, items: &Query<&PlacedItem>) -> Option<f32> {
+    let (distance, away) = path.nearest(point);
+    let on_path = away <= PATH_SNAP && distance > ITEM_SPACING && distance < path.length();
+    let crowded = items
+        .iter()
+        .any(|item| (item.distance - distance).abs() < ITEM_SPACING);
+    (on_path && !crowded).then_some(distance)
+}
+
+fn place_path_items(
+    mut commands: Commands,
+    (mut placement, mut player): (ResMut<ArmedPlacement>, Query<&mut Player>),
+    (cursor, mouse): (CursorGround, Res<Input<MouseButton>>),
+    (game_assets, item_assets): (Res<GameAssets>, Res<PathItemAssets>),
+    (path, items): (Res<TargetPath>, Query<&PlacedItem>),
+    mut marker: Query<(&mut Transform, &mut Visibility), With<PlacementMarker>>,
+) {
+    let Ok((mut marker_transform, mut marker_visibility)) = marker.get_single_mut() else {
+        return;
+    };
+    let Some(item) = placement.path_item() else {
+        marker_visibility.is_visible = false;
+        return;
+    };
+
+    let spot = cursor
+        .point()
+        .and_then(|point| placement_spot(point, &path, &items));
+    let Some(distance) = spot else {
+        marker_visibility.is_visible = false;
+        return;
+    };
+
+    let position = path.position_at(distance);
+    marker_transform.translation = position + Vec3::Y * 0.05;
+    marker_visibility.is_visible = true;
+    if mouse.just_pressed(MouseButton::Left) {
+        placement.armed = None;
+        if player.single_mut().funds.spend(&item.cost()) {
+            spawn_path_item(
+                &mut commands,
+                &game_assets,
+                &item_assets,
+                &path,
+                item,
+                distance,
+            );
+        }
+    }
+}
+
+fn spawn_path_item(
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    item_assets: &PathItemAssets,
+    path: &TargetPath,
+    item: PathItem,
+    distance: f32,
+) {
+    let position = path.position_at(distance);
+    // Square on to the path so walkers meet it head on
+    let direction = path.direction_at(distance) * Vec3::new(1.0, 0.0, 1.0);
+    let transform = Transform::from_translation(position).looking_at(position + direction, Vec3::Y);
+
+    let mut item_commands = match item {
+        PathItem::TarPit => commands.spawn(PbrBundle {
+            mesh: item_assets.tar_mesh.clone(),
+            material: item_assets.tar_material.clone(),
+            // Laid flat just above the path
+            transform: Transform::from_translation(position + Vec3::Y * 0.01)
+                .with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+            ..default()
+        }),
+        PathItem::SpikeTrap => commands.spawn(SceneBundle {
+            scene: game_assets.spikes_scene.clone(),
+            transform: transform.with_scale(Vec3::splat(0.5)),
+            ..default()
+        }),
+        PathItem::Barricade => commands.spawn(SceneBundle {
+            scene: game_assets.barricade_scene.clone(),
+            transform: transform.with_scale(Vec3::splat(0.6)),
+            ..default()
+        }),
+    };
+    item_commands
+        .insert(PlacedItem { distance })
+        .insert(Name::new(item.name()));
+    match item {
+        PathItem::SpikeTrap => {
+            item_commands.insert(SpikeTrap {
+                timer: Timer::from_seconds(SPIKE_INTERVAL, TimerMode::Repeating),
+            });
+        }
+        PathItem::TarPit => {
+            item_commands.insert(TarPit);
+        }
+        PathItem::Barricade => {
+            item_commands
+                .insert(Barricade)
+                .insert(Health::new(BARRICADE_HEALTH))
+                .insert(PathBlocker { distance });
+        }
+    }
+}
+
+/// Walkers within reach of an item, flying targets pass over everything on the path.
+fn walkers_on(item: &PlacedItem, targets: &Query<(Entity, &Target)>) -> Vec<Entity> {
+    targets
+        .iter()
+        .filter(|(_, target)| {
+            target.movement == Movement::Ground
+                && (target.distance - item.distance).abs() <= ITEM_REACH
+        })
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+fn spike_traps(
+    mut traps: Query<(&mut SpikeTrap, &PlacedItem)>,
+    targets: Query<(Entity, &Target)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (mut trap, item) in &mut traps {
+        if !trap.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        for target in walkers_on(item, &targets) {
+            damage_events.send(DamageEvent {
+                target,
+                amount: SPIKE_DAMAGE,
+                kind: DamageKind::Physical,
+                critical: false,
+                source: None,
+            });
+        }
+    }
+}
+
+fn tar_pits(
+    pits: Query<&PlacedItem, With<TarPit>>,
+    targets: Query<(Entity, &Target)>,
+    mut status_events: EventWriter<StatusEvent>,
+) {
+    for item in &pits {
+        for target in walkers_on(item, &targets) {
+            // Kept topped up while standing in it, wearing off soon after leaving
+            status_events.send(StatusEvent {
+                target,
+                effect: StatusEffect {
+                    name: "Tarred",
+                    speed_multiplier: TAR_SLOW,
+                    remaining: 0.5,
+                },
+            });
+        }
+    }
+}
+
+fn destroy_barricades(
+    mut commands: Commands,
+    barricades: Query<(Entity, &Health), With<Barricade>>,
+) {
+    for (entity, health) in &barricades {
+        if health.value <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn update_path_item_bar(
+    placement: Res<ArmedPlacement>,
+    player: Query<&Player>,
+    mut buttons: Query<(&PathItem, &mut BackgroundColor)>,
+) {
+    let player = player.single();
+    for (item, mut tint) in &mut buttons {
+        let armed = placement.path_item() == Some(*item);
+        *tint = hotkey_button_tint(armed, player.funds.can_afford(&item.cost()));
+    }
+}
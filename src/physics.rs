@@ -1,7 +1,10 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use crate::{Bullet, GameState, Health, Target};
+use crate::{
+    Bullet, DamageEvent, DamageKind, ExplosionEvent, Explosive, GameState, Pierce, Released,
+    Ricochet, SpentBullets, Target, TargetGrid,
+};
 
 pub struct PhysicsPlugin;
 
@@ -18,7 +21,6 @@ pub struct PhysicsBundle {
     flags: ActiveEvents,
     active_collition_types: ActiveCollisionTypes,
     collider: Collider,
-    colliding_entities: CollidingEntities,
     rigid_body: RigidBody,
     rotation_contraint: LockedAxes,
     velocity: Velocity,
@@ -31,7 +33,6 @@ impl PhysicsBundle {
             active_collition_types: ActiveCollisionTypes::default()
                 | ActiveCollisionTypes::KINEMATIC_KINEMATIC,
             collider: Collider::cuboid(size.x / 2., size.y / 2., size.z / 2.),
-            colliding_entities: CollidingEntities::default(),
             rigid_body: RigidBody::KinematicPositionBased,
             rotation_contraint: LockedAxes::ROTATION_LOCKED,
             velocity: Velocity::zero(),
@@ -41,15 +42,325 @@ impl PhysicsBundle {
 
 fn bullet_collision_detection(
     mut commands: Commands,
-    bullets: Query<Entity, With<Bullet>>,
-    mut targets: Query<(&mut Health, &CollidingEntities), With<Target>>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut bullets: Query<
+        (
+            &mut Bullet,
+            &mut Transform,
+            &GlobalTransform,
+            Option<&Explosive>,
+            Option<&mut Pierce>,
+            Option<&mut Ricochet>,
+        ),
+        Without<Released>,
+    >,
+    targets: Query<(), With<Target>>,
+    grid: Res<TargetGrid>,
+    (mut damage_events, mut explosion_events): (
+        EventWriter<DamageEvent>,
+        EventWriter<ExplosionEvent>,
+    ),
+    // Bullets used up earlier this frame, so a second contact can't hit again
+    mut spent: ResMut<SpentBullets>,
 ) {
-    for (mut health, colliding_entities) in &mut targets {
-        for bullet_entity in &bullets {
-            if colliding_entities.contains(bullet_entity) {
-                commands.entity(bullet_entity).despawn_recursive();
-                health.value -= 1.0;
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(first, second, _) = *event else {
+            continue;
+        };
+        // Rapier doesn't order the pair, the bullet may be either one
+        let (bullet_entity, target) = if targets.contains(second) {
+            (first, second)
+        } else if targets.contains(first) {
+            (second, first)
+        } else {
+            continue;
+        };
+        if spent.0.contains(&bullet_entity) {
+            continue;
+        }
+        let Ok((mut bullet, mut transform, bullet_transform, explosive, mut pierce, mut ricochet)) =
+            bullets.get_mut(bullet_entity)
+        else {
+            continue;
+        };
+
+        if let Some(explosive) = explosive {
+            // The blast covers whatever was hit directly
+            commands.entity(bullet_entity).insert(Released);
+            spent.0.push(bullet_entity);
+            explosion_events.send(ExplosionEvent {
+                position: bullet_transform.translation(),
+                explosive: *explosive,
+                source: bullet.source,
+            });
+            continue;
+        }
+
+        // Touching something it already went through or bounced off
+        let already_hit = pierce
+            .as_ref()
+            .is_some_and(|pierce| pierce.hit.contains(&target))
+            || ricochet
+                .as_ref()
+                .is_some_and(|ricochet| ricochet.hit.contains(&target));
+        if already_hit {
+            continue;
+        }
+
+        damage_events.send(DamageEvent {
+            target,
+            amount: bullet.damage,
+            kind: DamageKind::Physical,
+            critical: false,
+            source: bullet.source,
+        });
+
+        if let Some(pierce) = pierce.as_mut().filter(|pierce| pierce.remaining > 0) {
+            pierce.remaining -= 1;
+            pierce.hit.push(target);
+            continue;
+        }
+
+        if let Some(ricochet) = ricochet.as_mut().filter(|ricochet| ricochet.remaining > 0) {
+            ricochet.hit.push(target);
+            let position = bullet_transform.translation();
+            let next_target = grid.closest(position, ricochet.range, |entry| {
+                !ricochet.hit.contains(&entry.entity)
+            });
+
+            if let Some(next_target) = next_target {
+                ricochet.remaining -= 1;
+                bullet.direction = next_target.position - position;
+                bullet.damage *= 1.0 - ricochet.damage_falloff;
+                let facing = transform.translation + bullet.direction;
+                transform.look_at(facing, Vec3::Y);
+                continue;
             }
         }
+
+        commands.entity(bullet_entity).insert(Released);
+        spent.0.push(bullet_entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{drain_events, headless_app, send_event};
+    use crate::{detonate_at_point, CanTarget, GridEntry, Movement};
+    use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+    fn collision_app() -> App {
+        let mut app = headless_app();
+        app.init_resource::<TargetGrid>()
+            .init_resource::<SpentBullets>()
+            .add_event::<CollisionEvent>()
+            .add_event::<DamageEvent>()
+            .add_event::<ExplosionEvent>()
+            .add_system(bullet_collision_detection);
+        app
+    }
+
+    fn spawn_target(app: &mut App, position: Vec3) -> Entity {
+        let entity = app
+            .world
+            .spawn((
+                Target::default(),
+                GlobalTransform::from_translation(position),
+            ))
+            .id();
+        app.world.resource_mut::<TargetGrid>().insert(GridEntry {
+            entity,
+            position,
+            movement: Movement::Ground,
+        });
+        entity
+    }
+
+    fn spawn_bullet(app: &mut App, behaviour: impl Bundle) -> Entity {
+        let bullet = Bullet {
+            direction: Vec3::X,
+            speed: 10.0,
+            damage: 1.0,
+            range: 20.0,
+            ..default()
+        };
+        app.world
+            .spawn((
+                bullet,
+                Transform::default(),
+                GlobalTransform::default(),
+                behaviour,
+            ))
+            .id()
+    }
+
+    /// Starts a contact between `bullet` and each target in turn, one frame apiece,
+    /// returning every target that took damage.
+    fn touch_in_turn(app: &mut App, bullet: Entity, targets: &[Entity]) -> Vec<Entity> {
+        let mut damaged = Vec::new();
+        for &target in targets {
+            let contact = CollisionEvent::Started(bullet, target, CollisionEventFlags::empty());
+            send_event(app, contact);
+            app.update();
+            damaged.extend(
+                drain_events::<DamageEvent>(app)
+                    .iter()
+                    .map(|hit| hit.target),
+            );
+        }
+        damaged
+    }
+
+    /// Starts contacts between `bullet` and every target in the same frame.
+    fn touch_at_once(app: &mut App, bullet: Entity, targets: &[Entity]) {
+        for &target in targets {
+            let contact = CollisionEvent::Started(bullet, target, CollisionEventFlags::empty());
+            send_event(app, contact);
+        }
+        app.update();
+    }
+
+    #[test]
+    fn bullet_overlapping_two_targets_hits_only_one() {
+        let mut app = collision_app();
+        let targets = [
+            spawn_target(&mut app, Vec3::ZERO),
+            spawn_target(&mut app, Vec3::X * 0.1),
+        ];
+        let bullet = spawn_bullet(&mut app, ());
+
+        touch_at_once(&mut app, bullet, &targets);
+
+        let damaged: Vec<Entity> = drain_events::<DamageEvent>(&mut app)
+            .iter()
+            .map(|hit| hit.target)
+            .collect();
+        assert_eq!(damaged, [targets[0]]);
+        assert!(app.world.get::<Released>(bullet).is_some());
+    }
+
+    #[test]
+    fn explosive_bullet_explodes_once_when_hitting_at_its_detonation_point() {
+        let mut app = collision_app();
+        app.add_system(detonate_at_point);
+        let targets = [
+            spawn_target(&mut app, Vec3::ZERO),
+            spawn_target(&mut app, Vec3::X * 0.1),
+        ];
+        // Reaches its detonation point on the same frame it hits both targets
+        let bullet = spawn_bullet(
+            &mut app,
+            Explosive {
+                radius: 2.0,
+                damage: 3.0,
+                falloff: 0.5,
+                detonation_point: Some(Vec3::ZERO),
+                can_target: CanTarget {
+                    ground: true,
+                    air: false,
+                },
+            },
+        );
+
+        touch_at_once(&mut app, bullet, &targets);
+        assert_eq!(drain_events::<ExplosionEvent>(&mut app).len(), 1);
+
+        // Nothing is left to go off on later frames either
+        touch_at_once(&mut app, bullet, &targets);
+        assert!(drain_events::<ExplosionEvent>(&mut app).is_empty());
+    }
+
+    #[test]
+    fn pierce_hits_distinct_targets_then_is_released() {
+        let mut app = collision_app();
+        let targets: Vec<Entity> = (0..3)
+            .map(|i| spawn_target(&mut app, Vec3::X * i as f32))
+            .collect();
+        let bullet = spawn_bullet(
+            &mut app,
+            Pierce {
+                remaining: 2,
+                hit: Vec::new(),
+            },
+        );
+
+        // Still touching the first target on the way through the second
+        let contacts = [targets[0], targets[0], targets[1], targets[0], targets[2]];
+        let damaged = touch_in_turn(&mut app, bullet, &contacts);
+
+        assert_eq!(damaged, targets);
+        assert!(app.world.get::<Released>(bullet).is_some());
+    }
+
+    #[test]
+    fn pierce_keeps_flying_until_used_up() {
+        let mut app = collision_app();
+        let targets: Vec<Entity> = (0..2)
+            .map(|i| spawn_target(&mut app, Vec3::X * i as f32))
+            .collect();
+        let bullet = spawn_bullet(
+            &mut app,
+            Pierce {
+                remaining: 2,
+                hit: Vec::new(),
+            },
+        );
+
+        touch_in_turn(&mut app, bullet, &targets);
+
+        assert!(app.world.get::<Released>(bullet).is_none());
+        assert_eq!(app.world.get::<Pierce>(bullet).unwrap().remaining, 0);
+    }
+
+    #[test]
+    fn ricochet_never_hits_the_same_target_twice() {
+        let mut app = collision_app();
+        let targets: Vec<Entity> = (0..3)
+            .map(|i| spawn_target(&mut app, Vec3::X * 2.0 * i as f32))
+            .collect();
+        let bullet = spawn_bullet(
+            &mut app,
+            Ricochet {
+                remaining: 2,
+                range: 5.0,
+                damage_falloff: 0.5,
+                hit: Vec::new(),
+            },
+        );
+
+        let damaged = touch_in_turn(&mut app, bullet, &targets[..1]);
+        // Bounced towards the closest target it hasn't hit
+        assert_eq!(
+            app.world.get::<Bullet>(bullet).unwrap().direction,
+            Vec3::X * 2.0
+        );
+
+        let contacts = [targets[0], targets[1], targets[0], targets[1], targets[2]];
+        let damaged = [damaged, touch_in_turn(&mut app, bullet, &contacts)].concat();
+
+        assert_eq!(damaged, targets);
+        assert!(app.world.get::<Released>(bullet).is_some());
+    }
+
+    #[test]
+    fn ricochet_stops_with_nothing_left_in_range() {
+        let mut app = collision_app();
+        let first = spawn_target(&mut app, Vec3::ZERO);
+        spawn_target(&mut app, Vec3::X * 10.0);
+        let bullet = spawn_bullet(
+            &mut app,
+            Ricochet {
+                remaining: 2,
+                range: 5.0,
+                damage_falloff: 0.5,
+                hit: Vec::new(),
+            },
+        );
+
+        let damaged = touch_in_turn(&mut app, bullet, &[first]);
+
+        assert_eq!(damaged, [first]);
+        assert!(app.world.get::<Released>(bullet).is_some());
     }
 }
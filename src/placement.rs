@@ -0,0 +1,128 @@
+use crate::{Ability, GameState, PathItem};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_mod_picking::PickingCamera;
+
+/// Something following the cursor until a click on the map puts it down.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Armed {
+    /// Cast where the click lands
+    Ability(Ability),
+    /// Built on the path nearest the click
+    PathItem(PathItem),
+}
+
+/// What is following the cursor, if anything. Only one thing can at a time, so arming
+/// another puts the last one away.
+#[derive(Resource, Default)]
+pub struct ArmedPlacement {
+    pub armed: Option<Armed>,
+}
+
+impl ArmedPlacement {
+    /// Arms `armed`, or puts it away if it already was.
+    pub fn toggle(&mut self, armed: Armed) {
+        self.armed = match self.armed == Some(armed) {
+            true => None,
+            false => Some(armed),
+        };
+    }
+
+    pub fn ability(&self) -> Option<Ability> {
+        match self.armed {
+            Some(Armed::Ability(ability)) => Some(ability),
+            _ => None,
+        }
+    }
+
+    pub fn path_item(&self) -> Option<PathItem> {
+        match self.armed {
+            Some(Armed::PathItem(item)) => Some(item),
+            _ => None,
+        }
+    }
+}
+
+/// The map's ground plane, the only thing aiming and placement look for under the cursor.
+#[derive(Component)]
+pub struct Ground;
+
+/// Finds the point on the ground under the cursor, for aiming abilities and placing items.
+#[derive(SystemParam)]
+pub struct CursorGround<'w, 's> {
+    cameras: Query<'w, 's, &'static PickingCamera>,
+    ground: Query<'w, 's, (), With<Ground>>,
+    windows: Res<'w, Windows>,
+    ui_nodes: Query<
+        'w,
+        's,
+        (
+            &'static Node,
+            &'static GlobalTransform,
+            &'static BackgroundColor,
+            &'static ComputedVisibility,
+        ),
+    >,
+}
+
+impl CursorGround<'_, '_> {
+    /// Where the cursor is over the map, `None` while it is off the map or over the UI.
+    pub fn point(&self) -> Option<Vec3> {
+        if self.over_ui() {
+            return None;
+        }
+        // Towers and enemies are pickable too, so look past them to the ground
+        self.cameras
+            .iter()
+            .filter_map(|camera| camera.intersect_list())
+            .flatten()
+            .find(|(entity, _)| self.ground.contains(*entity))
+            .map(|(_, intersection)| intersection.position())
+    }
+
+    /// Whether the cursor is over any drawn UI node, whether or not it can be clicked.
+    fn over_ui(&self) -> bool {
+        let Some(window) = self.windows.get_primary() else {
+            return false;
+        };
+        let Some(cursor) = window.cursor_position() else {
+            return false;
+        };
+        // UI positions are measured down from the top of the window
+        let cursor = Vec2::new(cursor.x, window.height() - cursor.y);
+        self.ui_nodes
+            .iter()
+            // Transparent nodes only lay out their children
+            .filter(|(_, _, background, visibility)| {
+                visibility.is_visible() && background.0.a() > 0.0
+            })
+            .any(|(node, transform, _, _)| {
+                let center = transform.translation().truncate();
+                let extents = node.size() / 2.0;
+                (center - extents).cmplt(cursor).all() && cursor.cmplt(center + extents).all()
+            })
+    }
+}
+
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ArmedPlacement>().add_system_set(
+            SystemSet::on_update(GameState::Gameplay).with_system(cancel_placement),
+        );
+    }
+}
+
+/// Right click or Escape puts away whatever is armed.
+pub fn cancel_placement(
+    mut placement: ResMut<ArmedPlacement>,
+    mouse: Res<Input<MouseButton>>,
+    keyboard: Res<Input<KeyCode>>,
+) {
+    if placement.armed.is_some()
+        && (mouse.just_pressed(MouseButton::Right) || keyboard.just_pressed(KeyCode::Escape))
+    {
+        placement.armed = None;
+    }
+}
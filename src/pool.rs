@@ -0,0 +1,187 @@
+use crate::{
+    Bullet, ExplosionEffect, Explosive, GameAssets, GameState, Homing, Lifetime, PhysicsBundle,
+    Pierce, Ricochet, Shell,
+};
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// How many of each bullet are spawned up front so the first volleys don't miss the pool.
+const PREWARM_COUNT: usize = 32;
+
+pub const POOL_HITS: DiagnosticId =
+    DiagnosticId::from_u128(271638203657394021856342095129381570311);
+pub const POOL_MISSES: DiagnosticId =
+    DiagnosticId::from_u128(95028127764313308416235290378826501542);
+
+/// What a pooled entity looks like, entities are only reused for the same kind.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum PoolKind {
+    Scene(Handle<Scene>),
+    Mesh(Handle<Mesh>, Handle<StandardMaterial>),
+}
+
+#[derive(Component)]
+pub struct Pooled {
+    kind: PoolKind,
+}
+
+/// Marks an entity as finished with. Pooled entities are hidden and handed back
+/// to the pool at the end of the frame, anything else is despawned.
+#[derive(Component)]
+pub struct Released;
+
+/// Bullets released earlier this frame. `Released` only lands once commands are
+/// applied, so systems that use bullets up check here to avoid using one twice.
+#[derive(Resource, Default)]
+pub struct SpentBullets(pub Vec<Entity>);
+
+/// Recycles projectiles and effects instead of spawning and despawning them,
+/// which avoids instancing a new scene for every shot.
+#[derive(Resource)]
+pub struct ProjectilePool {
+    /// When off every acquire spawns and every release despawns, for comparing against
+    pub enabled: bool,
+    free: HashMap<PoolKind, Vec<Entity>>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl ProjectilePool {
+    pub fn new(enabled: bool) -> Self {
+        ProjectilePool {
+            enabled,
+            free: HashMap::default(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// A visible entity of `kind` at `transform`, reused from the pool if one is free.
+    pub fn acquire(
+        &mut self,
+        commands: &mut Commands,
+        kind: &PoolKind,
+        transform: Transform,
+    ) -> Entity {
+        // Hits and misses only mean something while pooling
+        if !self.enabled {
+            return spawn_pooled(commands, kind, transform, Visibility::VISIBLE);
+        }
+        match self.free.get_mut(kind).and_then(Vec::pop) {
+            Some(entity) => {
+                self.hits += 1;
+                commands
+                    .entity(entity)
+                    .insert(transform)
+                    .insert(Visibility::VISIBLE);
+                entity
+            }
+            None => {
+                self.misses += 1;
+                spawn_pooled(commands, kind, transform, Visibility::VISIBLE)
+            }
+        }
+    }
+}
+
+pub struct PoolPlugin;
+
+impl Plugin for PoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ProjectilePool::new(
+            std::env::var("PROJECTILE_POOL").as_deref() != Ok("off"),
+        ))
+        .init_resource::<SpentBullets>()
+        .add_startup_system(pool_diagnostics_setup)
+        .add_system(pool_diagnostics)
+        .add_system_set(SystemSet::on_enter(GameState::Gameplay).with_system(prewarm_pool))
+        .add_system_to_stage(CoreStage::PostUpdate, recycle_released);
+    }
+}
+
+fn spawn_pooled(
+    commands: &mut Commands,
+    kind: &PoolKind,
+    transform: Transform,
+    visibility: Visibility,
+) -> Entity {
+    let mut entity_commands = match kind {
+        PoolKind::Scene(scene) => commands.spawn(SceneBundle {
+            scene: scene.clone(),
+            transform,
+            visibility,
+            ..default()
+        }),
+        PoolKind::Mesh(mesh, material) => commands.spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            transform,
+            visibility,
+            ..default()
+        }),
+    };
+    entity_commands.insert(Pooled { kind: kind.clone() }).id()
+}
+
+fn prewarm_pool(mut commands: Commands, mut pool: ResMut<ProjectilePool>, assets: Res<GameAssets>) {
+    if !pool.enabled {
+        return;
+    }
+
+    let bullet_scenes = [
+        &assets.tower_a_bullet_scene,
+        &assets.tower_b_bullet_scene,
+        &assets.tower_c_bullet_scene,
+        &assets.tower_d_bullet_scene,
+    ];
+    for scene in bullet_scenes {
+        let kind = PoolKind::Scene(scene.clone());
+        // Towers sharing a model share a pool
+        if pool.free.contains_key(&kind) {
+            continue;
+        }
+        let entities = (0..PREWARM_COUNT)
+            .map(|_| spawn_pooled(&mut commands, &kind, default(), Visibility::INVISIBLE))
+            .collect();
+        pool.free.insert(kind, entities);
+    }
+}
+
+fn recycle_released(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    mut spent: ResMut<SpentBullets>,
+    released: Query<(Entity, Option<&Pooled>), With<Released>>,
+) {
+    // Everything spent this frame has its `Released` by now
+    spent.0.clear();
+    for (entity, pooled) in &released {
+        match pooled {
+            Some(pooled) if pool.enabled => {
+                commands
+                    .entity(entity)
+                    .remove::<Released>()
+                    .remove::<(Bullet, Homing, Explosive, Pierce, Ricochet)>()
+                    .remove::<(Shell, Lifetime, ExplosionEffect)>()
+                    .remove::<PhysicsBundle>()
+                    .insert(Visibility::INVISIBLE);
+                pool.free
+                    .entry(pooled.kind.clone())
+                    .or_default()
+                    .push(entity);
+            }
+            _ => commands.entity(entity).despawn_recursive(),
+        }
+    }
+}
+
+fn pool_diagnostics_setup(mut diagnostics: ResMut<Diagnostics>) {
+    diagnostics.add(Diagnostic::new(POOL_HITS, "projectile_pool_hits", 1));
+    diagnostics.add(Diagnostic::new(POOL_MISSES, "projectile_pool_misses", 1));
+}
+
+fn pool_diagnostics(mut diagnostics: ResMut<Diagnostics>, pool: Res<ProjectilePool>) {
+    diagnostics.add_measurement(POOL_HITS, || pool.hits as f64);
+    diagnostics.add_measurement(POOL_MISSES, || pool.misses as f64);
+}
@@ -0,0 +1,111 @@
+use crate::GameAssets;
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_resource::{
+    Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+};
+
+/// Width and height in pixels of a rendered portrait.
+const PORTRAIT_SIZE: u32 = 128;
+/// Where portrait models are posed, out of sight under the ground.
+const PORTRAIT_STAGE: Vec3 = Vec3::new(0.0, -100.0, 0.0);
+
+/// Renders a model into an icon for the UI, kept apart from the gameplay camera.
+#[derive(Component)]
+pub struct PortraitCamera;
+
+/// The model posed in front of a `PortraitCamera`.
+#[derive(Component)]
+struct PortraitModel;
+
+/// A blank image for a `PortraitCamera` to render into.
+pub fn new_portrait_image(images: &mut Assets<Image>) -> Handle<Image> {
+    let size = Extent3d {
+        width: PORTRAIT_SIZE,
+        height: PORTRAIT_SIZE,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+        },
+        ..default()
+    };
+    image.resize(size);
+    images.add(image)
+}
+
+pub struct PortraitPlugin;
+
+impl Plugin for PortraitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(spawn_barbarian_portrait)
+            .add_system(stop_portrait_cameras);
+    }
+}
+
+fn spawn_barbarian_portrait(mut commands: Commands, assets: Res<GameAssets>) {
+    commands
+        .spawn(SceneBundle {
+            scene: assets.target_scene.clone(),
+            transform: Transform::from_translation(PORTRAIT_STAGE),
+            ..default()
+        })
+        .insert(PortraitModel)
+        .insert(Name::new("Barbarian Portrait"));
+    commands
+        .spawn(PointLightBundle {
+            transform: Transform::from_translation(PORTRAIT_STAGE + Vec3::new(1.0, 2.0, 2.0)),
+            ..default()
+        })
+        .insert(Name::new("Portrait Light"));
+    commands
+        .spawn(Camera3dBundle {
+            camera_3d: Camera3d {
+                clear_color: ClearColorConfig::Custom(Color::NONE),
+                ..default()
+            },
+            camera: Camera {
+                // Before the main camera so the icon is ready when the UI draws it
+                priority: -1,
+                target: RenderTarget::Image(assets.barbarian_icon.clone()),
+                ..default()
+            },
+            // Head and shoulders, slightly from the side
+            transform: Transform::from_translation(PORTRAIT_STAGE + Vec3::new(0.5, 0.9, 1.6))
+                .looking_at(PORTRAIT_STAGE + Vec3::Y * 0.6, Vec3::Y),
+            ..default()
+        })
+        .insert(UiCameraConfig { show_ui: false })
+        .insert(PortraitCamera)
+        .insert(Name::new("Portrait Camera"));
+}
+
+/// Portraits never change, so the cameras stop rendering after the first frame
+/// with the model in it. The scene loads in the background, so that may not be
+/// the very first frame.
+fn stop_portrait_cameras(
+    mut cameras: Query<&mut Camera, With<PortraitCamera>>,
+    models: Query<(), (With<PortraitModel>, With<Children>)>,
+    mut rendered: Local<bool>,
+) {
+    if *rendered {
+        for mut camera in &mut cameras {
+            if camera.is_active {
+                camera.is_active = false;
+            }
+        }
+    } else if !models.is_empty() {
+        // Drawn at the end of this frame
+        *rendered = true;
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{GameAssets, GameState, Tower, TowerBase, TowerButtonState, TowerType, UpgradeButton};
+use bevy::pbr::NotShadowCaster;
+use bevy::prelude::*;
+use bevy_mod_picking::{Hover, Selection};
+
+/// Ring on the ground around a tower showing how far it can shoot.
+#[derive(Component)]
+pub struct RangeRing;
+
+pub struct RangeIndicatorPlugin;
+
+impl Plugin for RangeIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(GameState::Gameplay).with_system(spawn_range_ring))
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay).with_system(update_range_ring),
+            );
+    }
+}
+
+fn spawn_range_ring(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands
+        .spawn(PbrBundle {
+            // Unit radius, scaled up to the range being shown
+            mesh: meshes.add(Mesh::from(shape::Torus {
+                radius: 1.0,
+                ring_radius: 0.02,
+                subdivisions_segments: 64,
+                subdivisions_sides: 6,
+            })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(1.0, 1.0, 1.0, 0.7),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            visibility: Visibility::INVISIBLE,
+            ..default()
+        })
+        .insert(NotShadowCaster)
+        .insert(RangeRing)
+        .insert(Name::new("Range Ring"));
+}
+
+fn update_range_ring(
+    mut ring: Query<(&mut Transform, &mut Visibility), With<RangeRing>>,
+    towers: Query<(&GlobalTransform, &Tower, &Hover, &Selection)>,
+    bases: Query<(&GlobalTransform, &Selection), With<TowerBase>>,
+    build_buttons: Query<(&Interaction, &TowerType), With<TowerButtonState>>,
+    upgrade_buttons: Query<(&Interaction, &UpgradeButton)>,
+    assets: Res<GameAssets>,
+) {
+    let Ok((mut transform, mut visibility)) = ring.get_single_mut() else {
+        return;
+    };
+    let hovered = |interaction: &Interaction| !matches!(interaction, Interaction::None);
+
+    // Previewing a tower on the selected base
+    let build_preview = build_buttons
+        .iter()
+        .find(|(interaction, _)| hovered(interaction))
+        .and_then(|(_, tower_type)| {
+            let (base_transform, _) = bases.iter().find(|(_, selection)| selection.selected())?;
+            let (_, tower) = tower_type.get_tower(&assets);
+            Some((base_transform.translation(), tower.range))
+        });
+    // Previewing an upgrade on the selected tower
+    let upgrade_preview = || {
+        let (_, button) = upgrade_buttons
+            .iter()
+            .find(|(interaction, _)| hovered(interaction))?;
+        let (tower_transform, tower, _, _) = towers.get(button.tower).ok()?;
+        Some((tower_transform.translation(), tower.upgraded_range()))
+    };
+    let hovered_tower = || {
+        towers
+            .iter()
+            .find(|(_, _, hover, _)| hover.hovered())
+            .or_else(|| {
+                towers
+                    .iter()
+                    .find(|(_, _, _, selection)| selection.selected())
+            })
+            .map(|(tower_transform, tower, _, _)| (tower_transform.translation(), tower.range))
+    };
+
+    match build_preview
+        .or_else(upgrade_preview)
+        .or_else(hovered_tower)
+    {
+        Some((center, range)) => {
+            // Flat on the ground, just above it so it doesn't z-fight
+            transform.translation = Vec3::new(center.x, 0.02, center.z);
+            transform.scale = Vec3::new(range, 1.0, range);
+            visibility.is_visible = true;
+        }
+        None => visibility.is_visible = false,
+    }
+}
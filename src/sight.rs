@@ -0,0 +1,165 @@
+use crate::{
+    line_between, CanTarget, GameAssets, GameState, Lifetime, PoolKind, ProjectilePool, TargetGrid,
+    Tower,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Terrain that blocks the view of towers with `LineOfSight`.
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Obstacle;
+
+/// Towers with this can only fire at targets they can see past obstacles.
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct LineOfSight;
+
+/// Toggled with F3, draws a red line for every target a tower can't see.
+#[derive(Resource, Default)]
+pub struct SightDebug {
+    pub enabled: bool,
+}
+
+#[derive(Resource)]
+pub struct SightAssets {
+    line_mesh: Handle<Mesh>,
+    blocked_material: Handle<StandardMaterial>,
+}
+
+pub struct SightPlugin;
+
+impl Plugin for SightPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Obstacle>()
+            .register_type::<LineOfSight>()
+            .init_resource::<SightDebug>()
+            .add_startup_system(sight_asset_loading)
+            .add_system_set(SystemSet::on_enter(GameState::Gameplay).with_system(spawn_obstacles))
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    .with_system(toggle_sight_debug)
+                    .with_system(draw_blocked_sight_lines),
+            );
+    }
+}
+
+/// True when nothing marked `Obstacle` is between `from` and `to`.
+pub fn has_line_of_sight(
+    rapier_context: &RapierContext,
+    obstacles: &Query<(), With<Obstacle>>,
+    from: Vec3,
+    to: Vec3,
+) -> bool {
+    let Some(direction) = (to - from).try_normalize() else {
+        return true;
+    };
+    let is_obstacle = |entity| obstacles.contains(entity);
+    let filter = QueryFilter::default().predicate(&is_obstacle);
+    rapier_context
+        .cast_ray(from, direction, from.distance(to), true, filter)
+        .is_none()
+}
+
+fn sight_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(SightAssets {
+        line_mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+        blocked_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.1, 0.1),
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+fn spawn_obstacles(mut commands: Commands, assets: Res<GameAssets>) {
+    // Model, position and collider size in model units, before the scene is scaled up
+    let obstacles = [
+        (
+            &assets.rocks_scene,
+            Vec3::new(9.0, 0.0, 4.0),
+            Vec3::new(0.8, 1.0, 0.8),
+        ),
+        (
+            &assets.tree_scene,
+            Vec3::new(21.0, 0.0, 4.5),
+            Vec3::new(0.6, 2.0, 0.6),
+        ),
+        (
+            &assets.rocks_scene,
+            Vec3::new(27.0, 0.0, 9.5),
+            Vec3::new(0.8, 1.0, 0.8),
+        ),
+        (
+            &assets.tree_scene,
+            Vec3::new(33.0, 0.0, 3.0),
+            Vec3::new(0.6, 2.0, 0.6),
+        ),
+    ];
+    for (scene, position, extents) in obstacles {
+        commands
+            .spawn(SceneBundle {
+                scene: scene.clone(),
+                transform: Transform::from_translation(position).with_scale(Vec3::splat(2.0)),
+                ..default()
+            })
+            .insert(Obstacle)
+            .insert(RigidBody::Fixed)
+            .insert(Collider::compound(vec![(
+                // Sat on the ground rather than centred on it
+                Vec3::Y * extents.y / 2.0,
+                Quat::IDENTITY,
+                Collider::cuboid(extents.x / 2.0, extents.y / 2.0, extents.z / 2.0),
+            )]))
+            .insert(Name::new("Obstacle"));
+    }
+}
+
+fn toggle_sight_debug(keyboard: Res<Input<KeyCode>>, mut debug: ResMut<SightDebug>) {
+    if keyboard.just_pressed(KeyCode::F3) {
+        debug.enabled = !debug.enabled;
+    }
+}
+
+fn draw_blocked_sight_lines(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    assets: Res<SightAssets>,
+    debug: Res<SightDebug>,
+    rapier_context: Res<RapierContext>,
+    obstacles: Query<(), With<Obstacle>>,
+    towers: Query<(&Tower, &CanTarget, &GlobalTransform), With<LineOfSight>>,
+    grid: Res<TargetGrid>,
+) {
+    if !debug.enabled {
+        return;
+    }
+
+    let kind = PoolKind::Mesh(assets.line_mesh.clone(), assets.blocked_material.clone());
+    for (tower, can_target, transform) in &towers {
+        let origin = transform.translation() + tower.bullet_offset;
+        for entry in grid.within(origin, tower.range) {
+            if !can_target.allows(entry.movement)
+                || has_line_of_sight(&rapier_context, &obstacles, origin, entry.position)
+            {
+                continue;
+            }
+            let line = pool.acquire(
+                &mut commands,
+                &kind,
+                line_between(origin, entry.position, 0.03),
+            );
+            // Redrawn every frame while the sight line stays blocked
+            commands
+                .entity(line)
+                .insert(Lifetime {
+                    timer: Timer::from_seconds(0.0, TimerMode::Once),
+                })
+                .insert(Name::new("Blocked sight line"));
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{Movement, Target};
+use bevy::prelude::*;
+use bevy::utils::{FloatOrd, HashMap};
+
+/// Width of a grid cell on the ground plane, about the range of a typical tower
+/// so most queries only touch a handful of cells.
+const CELL_SIZE: f32 = 4.0;
+
+#[derive(Clone, Copy)]
+pub struct GridEntry {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub movement: Movement,
+}
+
+/// Every target bucketed by its position on the ground plane, rebuilt at the
+/// start of each frame so towers and blasts only look at targets nearby.
+#[derive(Resource, Default)]
+pub struct TargetGrid {
+    cells: HashMap<IVec2, Vec<GridEntry>>,
+}
+
+impl TargetGrid {
+    pub fn insert(&mut self, entry: GridEntry) {
+        self.cells
+            .entry(cell_of(entry.position))
+            .or_default()
+            .push(entry);
+    }
+
+    pub fn clear(&mut self) {
+        // Keep the buckets around so they don't have to be reallocated next frame
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    /// Every target strictly closer than `radius` to `center`.
+    pub fn within(&self, center: Vec3, radius: f32) -> impl Iterator<Item = &GridEntry> {
+        let min = cell_of(center - Vec3::splat(radius));
+        let max = cell_of(center + Vec3::splat(radius));
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |entry| entry.position.distance(center) < radius)
+    }
+
+    /// The closest target within `radius` of `center` that passes `filter`.
+    pub fn closest(
+        &self,
+        center: Vec3,
+        radius: f32,
+        filter: impl Fn(&GridEntry) -> bool,
+    ) -> Option<&GridEntry> {
+        self.within(center, radius)
+            .filter(|entry| filter(entry))
+            .min_by_key(|entry| FloatOrd(entry.position.distance(center)))
+    }
+}
+
+fn cell_of(position: Vec3) -> IVec2 {
+    IVec2::new(
+        (position.x / CELL_SIZE).floor() as i32,
+        (position.z / CELL_SIZE).floor() as i32,
+    )
+}
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TargetGrid>()
+            .add_system_to_stage(CoreStage::PreUpdate, rebuild_target_grid);
+    }
+}
+
+fn rebuild_target_grid(
+    mut grid: ResMut<TargetGrid>,
+    targets: Query<(Entity, &GlobalTransform, &Target)>,
+) {
+    grid.clear();
+    for (entity, transform, target) in &targets {
+        grid.insert(GridEntry {
+            entity,
+            position: transform.translation(),
+            movement: target.movement,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_of(positions: &[Vec3]) -> TargetGrid {
+        let mut grid = TargetGrid::default();
+        for (index, &position) in positions.iter().enumerate() {
+            grid.insert(GridEntry {
+                entity: Entity::from_raw(index as u32),
+                position,
+                movement: Movement::Ground,
+            });
+        }
+        grid
+    }
+
+    fn found(grid: &TargetGrid, center: Vec3, radius: f32) -> Vec<u32> {
+        let mut indices: Vec<u32> = grid
+            .within(center, radius)
+            .map(|entry| entry.entity.index())
+            .collect();
+        indices.sort();
+        indices
+    }
+
+    #[test]
+    fn within_reaches_into_neighbouring_cells() {
+        let grid = grid_of(&[
+            // Just across the boundary on either side of the query's own cell
+            Vec3::new(CELL_SIZE + 0.2, 0.0, 0.0),
+            Vec3::new(-0.2, 0.0, -0.2),
+            // In a neighbouring cell but out of range
+            Vec3::new(CELL_SIZE + 1.5, 0.0, 0.0),
+        ]);
+        assert_eq!(
+            found(&grid, Vec3::new(CELL_SIZE - 0.2, 0.0, 0.0), 1.0),
+            vec![0]
+        );
+        assert_eq!(found(&grid, Vec3::new(0.2, 0.0, 0.2), 1.0), vec![1]);
+    }
+
+    #[test]
+    fn within_excludes_targets_at_exactly_the_radius() {
+        let grid = grid_of(&[Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -2.0)]);
+        assert!(found(&grid, Vec3::ZERO, 2.0).is_empty());
+        assert_eq!(found(&grid, Vec3::ZERO, 2.01), vec![0, 1]);
+    }
+
+    #[test]
+    fn closest_picks_the_nearest_target_passing_the_filter() {
+        let grid = grid_of(&[
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 3.9),
+            Vec3::new(-4.5, 0.0, 0.0),
+        ]);
+        let closest = |filter: fn(&GridEntry) -> bool| {
+            grid.closest(Vec3::ZERO, 4.0, filter)
+                .map(|entry| entry.entity.index())
+        };
+        assert_eq!(closest(|_| true), Some(0));
+        assert_eq!(closest(|entry| entry.entity.index() != 0), Some(1));
+        // The only other target is out of range
+        assert_eq!(closest(|entry| entry.entity.index() == 2), None);
+    }
+
+    #[test]
+    fn empty_grid_finds_nothing() {
+        let mut grid = TargetGrid::default();
+        assert!(found(&grid, Vec3::ZERO, 10.0).is_empty());
+        assert!(grid.closest(Vec3::ZERO, 10.0, |_| true).is_none());
+
+        // Cleared buckets are kept but must be empty
+        grid = grid_of(&[Vec3::new(1.0, 0.0, 1.0)]);
+        grid.clear();
+        assert!(found(&grid, Vec3::ZERO, 10.0).is_empty());
+        assert!(grid.closest(Vec3::ZERO, 10.0, |_| true).is_none());
+    }
+}
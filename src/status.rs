@@ -0,0 +1,82 @@
+use crate::GameState;
+use bevy::prelude::*;
+
+/// A temporary effect on a target, such as being slowed.
+#[derive(Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub name: &'static str,
+    /// Multiplies the target's speed while active, 0 stops it entirely
+    pub speed_multiplier: f32,
+    /// Seconds left before the effect wears off
+    pub remaining: f32,
+}
+
+/// Every effect currently on a target.
+#[derive(Component, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Adds an effect, reapplying one with the same name refreshes it instead of stacking.
+    pub fn apply(&mut self, effect: StatusEffect) {
+        match self
+            .effects
+            .iter_mut()
+            .find(|active| active.name == effect.name)
+        {
+            Some(active) => {
+                active.remaining = active.remaining.max(effect.remaining);
+                active.speed_multiplier = active.speed_multiplier.min(effect.speed_multiplier);
+            }
+            None => self.effects.push(effect),
+        }
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|effect| effect.speed_multiplier)
+            .product()
+    }
+}
+
+pub struct StatusEvent {
+    pub target: Entity,
+    pub effect: StatusEffect,
+}
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StatusEvent>().add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(apply_status_events)
+                .with_system(tick_status_effects),
+        );
+    }
+}
+
+fn apply_status_events(
+    mut targets: Query<&mut StatusEffects>,
+    mut status_events: EventReader<StatusEvent>,
+) {
+    for event in status_events.iter() {
+        if let Ok(mut effects) = targets.get_mut(event.target) {
+            effects.apply(event.effect);
+        }
+    }
+}
+
+fn tick_status_effects(mut targets: Query<&mut StatusEffects>, time: Res<Time>) {
+    for mut effects in &mut targets {
+        if effects.effects.is_empty() {
+            continue;
+        }
+        for effect in &mut effects.effects {
+            effect.remaining -= time.delta_seconds();
+        }
+        effects.effects.retain(|effect| effect.remaining > 0.0);
+    }
+}
@@ -1,99 +1,579 @@
-use crate::{GameAssets, GameState, PhysicsBundle};
-use bevy::{math::Vec3Swizzles, prelude::*};
+use crate::{
+    AirPath, Currency, Funds, GameAssets, GameState, PhysicsBundle, StatusEffects, TargetPath,
+    TowerStats,
+};
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::render::render_resource::Face;
+use bevy::utils::FloatOrd;
+use bevy_inspector_egui::Inspectable;
+use bevy_mod_picking::{Highlighting, PickableBundle};
+
+/// Height above the ground path that flying targets cruise at.
+pub const FLYING_ALTITUDE: f32 = 2.5;
+/// How far short of a `PathBlocker` ground targets stop to fight it.
+const BLOCKER_GAP: f32 = 0.6;
 
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct Target {
     pub speed: f32,
-    pub path_index: usize,
+    /// Distance travelled along the target's route, negative while still queued before the start.
+    pub distance: f32,
+    pub movement: Movement,
+    /// Whatever is standing in its way, attacked instead of walking on
+    pub blocked_by: Option<Entity>,
 }
 
-#[derive(Resource)]
-pub struct TargetPath {
-    pub waypoints: Vec<Vec2>,
+impl Target {
+    /// How fast it is moving along its route right now, after slows and while held up.
+    pub fn current_speed(&self, effects: &StatusEffects) -> f32 {
+        match self.blocked_by {
+            Some(_) => 0.0,
+            None => self.speed * effects.speed_multiplier(),
+        }
+    }
+}
+
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Movement {
+    /// Walks the `TargetPath`
+    #[default]
+    Ground,
+    /// Flies the `AirPath`, ignoring the ground path entirely
+    Flying,
+}
+
+impl Movement {
+    pub fn route<'a>(&self, ground: &'a TargetPath, air: &'a AirPath) -> &'a TargetPath {
+        match self {
+            Movement::Ground => ground,
+            Movement::Flying => air,
+        }
+    }
 }
 
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct Health {
     pub value: f32,
+    pub max: f32,
+    /// Where the most recent damage came from, credited with the kill
+    pub last_hit_by: Option<Entity>,
+    /// The kind of the most recent damage, which can raise the bounty
+    #[reflect(ignore)]
+    pub last_hit_kind: Option<DamageKind>,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health {
+            value: max,
+            max,
+            last_hit_by: None,
+            last_hit_kind: None,
+        }
+    }
+}
+
+/// Something on the ground path that walking targets have to stop and fight through.
+#[derive(Component)]
+pub struct PathBlocker {
+    /// Where along the `TargetPath` it stands
+    pub distance: f32,
+}
+
+/// Hits whatever is blocking the target's way.
+#[derive(Component)]
+pub struct Attacker {
+    pub damage: f32,
+    pub cooldown: Timer,
+}
+
+/// Soaks up damage before it reaches `Health`.
+#[derive(Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct Shield {
+    pub value: f32,
+    pub max: f32,
+}
+
+#[derive(Inspectable, Component, Clone, Copy, Debug)]
+pub enum EnemyType {
+    Barbarian,
+    Ufo,
+}
+
+impl EnemyType {
+    fn get_enemy(&self, assets: &GameAssets, distance: f32) -> (Handle<Scene>, Target, Health) {
+        match self {
+            EnemyType::Barbarian => (
+                assets.target_scene.clone(),
+                Target {
+                    speed: 0.3,
+                    distance,
+                    movement: Movement::Ground,
+                    blocked_by: None,
+                },
+                Health::new(3.0),
+            ),
+            EnemyType::Ufo => (
+                assets.flying_target_scene.clone(),
+                Target {
+                    speed: 0.5,
+                    distance,
+                    movement: Movement::Flying,
+                    blocked_by: None,
+                },
+                Health::new(2.0),
+            ),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyType::Barbarian => "Barbarian",
+            EnemyType::Ufo => "UFO",
+        }
+    }
+
+    pub fn icon(&self, assets: &GameAssets) -> Handle<Image> {
+        match self {
+            EnemyType::Barbarian => assets.barbarian_icon.clone(),
+            EnemyType::Ufo => assets.ufo_icon.clone(),
+        }
+    }
+
+    /// Anything that changes how towers have to deal with it, for the wave preview.
+    pub fn traits(&self) -> &'static [&'static str] {
+        match self {
+            EnemyType::Ufo => &["Flying", "Shielded"],
+            _ => &[],
+        }
+    }
+
+    /// Gold paid out for killing one.
+    pub fn bounty(&self) -> u32 {
+        match self {
+            EnemyType::Barbarian => 10,
+            EnemyType::Ufo => 15,
+        }
+    }
+
+    /// Rare currencies paid out on top of the bounty.
+    pub fn loot(&self) -> Funds {
+        match self {
+            EnemyType::Ufo => Funds::new(&[(Currency::Crystals, 1)]),
+            _ => Funds::default(),
+        }
+    }
+
+    /// Bounty multiplier for landing the killing blow with the damage it is weakest to.
+    pub fn bounty_multiplier(&self, kind: DamageKind) -> f32 {
+        match (self, kind) {
+            (EnemyType::Barbarian, DamageKind::Explosive) => 1.5,
+            (EnemyType::Ufo, DamageKind::Lightning) => 2.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Lives lost when one reaches the end of its route.
+    pub fn leak_damage(&self) -> u32 {
+        match self {
+            EnemyType::Barbarian => 1,
+            EnemyType::Ufo => 2,
+        }
+    }
+
+    /// Flying targets pass over blockers, so only walkers ever attack them.
+    fn get_attacker(&self) -> Option<Attacker> {
+        match self {
+            EnemyType::Barbarian => Some(Attacker {
+                damage: 1.0,
+                cooldown: Timer::from_seconds(1.0, TimerMode::Repeating),
+            }),
+            _ => None,
+        }
+    }
+
+    fn get_shield(&self) -> Option<Shield> {
+        match self {
+            EnemyType::Ufo => Some(Shield {
+                value: 1.0,
+                max: 1.0,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Invisible shell around the model that makes targets hoverable and selectable,
+/// drawn as an outline while hovered or selected.
+#[derive(Resource)]
+pub struct TargetPickingAssets {
+    mesh: Handle<Mesh>,
+    hidden_material: Handle<StandardMaterial>,
+    highlight_material: Handle<StandardMaterial>,
 }
 
 pub struct TargetPlugin;
 
-pub struct TargetDeathEvent;
+pub struct TargetDeathEvent {
+    /// Already despawned by the time the event is read
+    pub entity: Entity,
+    pub enemy_type: EnemyType,
+    /// The tower that landed the killing blow
+    pub killer: Option<Entity>,
+    pub killing_blow: Option<DamageKind>,
+    pub position: Vec3,
+}
+
+impl TargetDeathEvent {
+    /// Gold for the kill, raised when the killing blow was a weakness.
+    pub fn bounty(&self) -> u32 {
+        let multiplier = self
+            .killing_blow
+            .map_or(1.0, |kind| self.enemy_type.bounty_multiplier(kind));
+        (self.enemy_type.bounty() as f32 * multiplier).round() as u32
+    }
+}
+
+/// Health to take off a target or structure, everything that hurts them goes through this.
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub kind: DamageKind,
+    pub critical: bool,
+    /// The tower that dealt the damage, if it came from one
+    pub source: Option<Entity>,
+}
+
+/// What dealt the damage, shown on hits and checked against enemy weaknesses on kills.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageKind {
+    Physical,
+    Explosive,
+    Lightning,
+    Laser,
+}
 
 impl Plugin for TargetPlugin {
     fn build(&self, app: &mut App) {
+        let path = TargetPath::from_control_points(&[
+            Vec3::new(0.0, 0.0, 3.0),
+            Vec3::new(6.0, 0.0, 2.0),
+            // Up the hill and over the bridge
+            Vec3::new(18.0, 1.0, 6.0),
+            Vec3::new(30.0, 1.0, 10.0),
+            Vec3::new(40.0, 0.0, 5.0),
+            Vec3::new(50.0, 0.0, 1.0),
+        ]);
+        let air_path = AirPath::over(&path, FLYING_ALTITUDE);
+
         app.register_type::<Target>()
+            .register_type::<Movement>()
             .register_type::<Health>()
-            .add_system_set(SystemSet::on_enter(GameState::Gameplay).with_system(spawn_targets))
+            .register_type::<Shield>()
+            .add_startup_system(target_picking_asset_loading)
             .add_system_set(
                 SystemSet::on_update(GameState::Gameplay)
                     .with_system(move_targets)
+                    .with_system(attack_blockers.after(move_targets))
+                    .with_system(apply_damage.before(target_death))
                     .with_system(target_death),
             )
-            .insert_resource(TargetPath {
-                waypoints: vec![
-                    Vec2::new(6.0, 2.0),
-                    Vec2::new(30.0, 10.0),
-                    Vec2::new(50.0, 1.0),
-                ],
-            })
-            .add_event::<TargetDeathEvent>();
-    }
-}
-
-fn spawn_targets(mut commands: Commands, game_assets: Res<GameAssets>) {
-    for i in 0..20 {
-        let x: f32 = 2.0 * i as f32;
-        commands
-            .spawn(SceneBundle {
-                scene: game_assets.target_scene.clone(),
-                transform: Transform::from_xyz(-x, 0.0, 3.0),
-                ..default()
-            })
-            .insert(PhysicsBundle::moving_entity(Vec3::new(0.4, 0.4, 0.4)))
-            .insert(Target {
-                speed: 0.3,
-                ..default()
-            })
-            .insert(Health { value: 3.0 })
-            .insert(Name::new("Target"));
+            .insert_resource(path)
+            .insert_resource(air_path)
+            .add_event::<TargetDeathEvent>()
+            .add_event::<DamageEvent>();
+    }
+}
+
+fn target_picking_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let capsule = shape::Capsule {
+        radius: 0.35,
+        depth: 0.6,
+        ..default()
+    };
+    let mut mesh = Mesh::from(capsule);
+    // Targets stand on their origin, lift the shell so it wraps the model instead of its feet
+    let lift = capsule.radius + capsule.depth / 2.0;
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for position in positions {
+            position[1] += lift;
+        }
+    }
+
+    commands.insert_resource(TargetPickingAssets {
+        mesh: meshes.add(mesh),
+        hidden_material: materials.add(Color::NONE.into()),
+        // Only the inside of the shell is drawn, so the model covers all of it
+        // but a rim around its edge
+        highlight_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.9, 0.2),
+            unlit: true,
+            cull_mode: Some(Face::Front),
+            ..default()
+        }),
+    });
+}
+
+/// Spawns one enemy `distance` along its route, negative to queue it up before the start.
+pub fn spawn_target(
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    picking_assets: &TargetPickingAssets,
+    path: &TargetPath,
+    air_path: &AirPath,
+    enemy_type: EnemyType,
+    distance: f32,
+) -> Entity {
+    let (model, target, health) = enemy_type.get_enemy(game_assets, distance);
+    let route = target.movement.route(path, air_path);
+    let mut target_commands = commands.spawn(SceneBundle {
+        scene: model,
+        transform: Transform::from_translation(route.position_at(distance)),
+        ..default()
+    });
+    target_commands
+        .insert(PhysicsBundle::moving_entity(Vec3::new(0.4, 0.4, 0.4)))
+        .insert(target)
+        .insert(health)
+        .insert(enemy_type)
+        .insert(StatusEffects::default())
+        .insert(picking_assets.mesh.clone())
+        .insert(picking_assets.hidden_material.clone())
+        .insert(Highlighting {
+            initial: picking_assets.hidden_material.clone(),
+            hovered: Some(picking_assets.highlight_material.clone()),
+            pressed: Some(picking_assets.highlight_material.clone()),
+            selected: Some(picking_assets.highlight_material.clone()),
+        })
+        .insert(PickableBundle::default())
+        .insert(Name::new("Target"));
+    if let Some(shield) = enemy_type.get_shield() {
+        target_commands.insert(shield);
+    }
+    if let Some(attacker) = enemy_type.get_attacker() {
+        target_commands.insert(attacker);
+    }
+    target_commands.id()
+}
+
+fn apply_damage(
+    mut targets: Query<(&mut Health, Option<&mut Shield>)>,
+    mut towers: Query<&mut TowerStats>,
+    mut damage_events: EventReader<DamageEvent>,
+    time: Res<Time>,
+) {
+    for damage in damage_events.iter() {
+        // The target may already have been despawned or killed this frame
+        let Ok((mut health, shield)) = targets.get_mut(damage.target) else {
+            continue;
+        };
+        if health.value <= 0.0 {
+            continue;
+        }
+        let mut amount = damage.amount;
+        if let Some(mut shield) = shield {
+            let absorbed = amount.min(shield.value);
+            shield.value -= absorbed;
+            amount -= absorbed;
+        }
+        // Only what the target actually lost counts towards the tower's stats
+        let taken = amount.min(health.value);
+        health.value -= amount;
+        health.last_hit_by = damage.source;
+        health.last_hit_kind = Some(damage.kind);
+
+        if let Some(mut stats) = damage.source.and_then(|source| towers.get_mut(source).ok()) {
+            stats.record_damage(time.elapsed_seconds(), taken);
+        }
     }
 }
 
 fn target_death(
     mut commands: Commands,
-    targets: Query<(Entity, &Health)>,
+    targets: Query<(Entity, &Health, &EnemyType, &Transform)>,
     mut death_event_writer: EventWriter<TargetDeathEvent>,
 ) {
-    for (entity, target_health) in &targets {
+    for (entity, target_health, enemy_type, transform) in &targets {
         if target_health.value <= 0.0 {
-            death_event_writer.send(TargetDeathEvent);
+            death_event_writer.send(TargetDeathEvent {
+                entity,
+                enemy_type: *enemy_type,
+                killer: target_health.last_hit_by,
+                killing_blow: target_health.last_hit_kind,
+                position: transform.translation,
+            });
             commands.entity(entity).despawn_recursive();
         }
     }
 }
 
 fn move_targets(
-    mut targets: Query<(&mut Target, &mut Transform)>,
+    mut targets: Query<(&mut Target, &StatusEffects, &mut Transform)>,
+    blockers: Query<(Entity, &PathBlocker)>,
     path: Res<TargetPath>,
+    air_path: Res<AirPath>,
     time: Res<Time>,
 ) {
-    for (mut target, mut transform) in &mut targets {
-        let delta = target.speed * time.delta_seconds();
-        let delta_target = path.waypoints[target.path_index] - transform.translation.xz();
-
-        if delta_target.length().round() > delta {
-            let movement = delta_target.normalize() * delta;
-            transform.translation += movement.extend(0.0).xzy();
-            let y = transform.translation.y;
-            transform.look_at(path.waypoints[target.path_index].extend(y).xzy(), Vec3::Y);
-        } else {
-            //At current step
-            target.path_index += 1;
-        }
-        transform.translation.x += target.speed * time.delta_seconds();
+    for (mut target, effects, mut transform) in &mut targets {
+        let path = target.movement.route(&path, &air_path);
+        // Walkers are held up by the first blocker ahead of them
+        let blocker = match target.movement {
+            Movement::Ground => blockers
+                .iter()
+                .filter(|(_, blocker)| blocker.distance >= target.distance)
+                .min_by_key(|(_, blocker)| FloatOrd(blocker.distance)),
+            Movement::Flying => None,
+        };
+        let previous = target.distance;
+        target.distance += target.speed * effects.speed_multiplier() * time.delta_seconds();
+        target.blocked_by = None;
+        if let Some((entity, blocker)) = blocker {
+            let stop = blocker.distance - BLOCKER_GAP;
+            if target.distance >= stop {
+                // A blocker put down closer than the gap holds the walker where it
+                // is rather than pushing it back
+                target.distance = stop.max(previous);
+                target.blocked_by = Some(entity);
+            }
+        }
+        transform.translation = path.position_at(target.distance);
+
+        // Face along the path but stay upright on slopes
+        let direction = path.direction_at(target.distance) * Vec3::new(1.0, 0.0, 1.0);
+        if direction != Vec3::ZERO {
+            let facing = transform.translation + direction;
+            transform.look_at(facing, Vec3::Y);
+        }
+    }
+}
+
+fn attack_blockers(
+    mut attackers: Query<(&Target, &mut Attacker)>,
+    mut damage_events: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (target, mut attacker) in &mut attackers {
+        let Some(blocker) = target.blocked_by else {
+            // The first swing comes a full cooldown after being stopped
+            attacker.cooldown.reset();
+            continue;
+        };
+        if attacker.cooldown.tick(time.delta()).just_finished() {
+            damage_events.send(DamageEvent {
+                target: blocker,
+                amount: attacker.damage,
+                kind: DamageKind::Physical,
+                critical: false,
+                source: None,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{headless_app, send_event};
+
+    fn hit(app: &mut App, tower: Entity, target: Entity, amount: f32) {
+        send_event(
+            app,
+            DamageEvent {
+                target,
+                amount,
+                kind: DamageKind::Physical,
+                critical: false,
+                source: Some(tower),
+            },
+        );
+        app.update();
+    }
+
+    #[test]
+    fn towers_are_credited_only_with_damage_taken() {
+        let mut app = headless_app();
+        app.add_event::<DamageEvent>().add_system(apply_damage);
+        let tower = app.world.spawn(TowerStats::default()).id();
+        let target = app
+            .world
+            .spawn((
+                Health::new(5.0),
+                Shield {
+                    value: 2.0,
+                    max: 2.0,
+                },
+            ))
+            .id();
+        let damage_dealt = |app: &App| app.world.get::<TowerStats>(tower).unwrap().damage_dealt;
+
+        // Two of the three are soaked up by the shield
+        hit(&mut app, tower, target, 3.0);
+        assert_eq!(damage_dealt(&app), 1.0);
+        // Overkill only counts the health that was left
+        hit(&mut app, tower, target, 10.0);
+        assert_eq!(damage_dealt(&app), 5.0);
+        // Hitting what is already dead counts for nothing
+        hit(&mut app, tower, target, 2.0);
+        assert_eq!(damage_dealt(&app), 5.0);
+    }
+
+    fn walking_app() -> App {
+        let mut app = headless_app();
+        let path = TargetPath::from_control_points(&[Vec3::ZERO, Vec3::X * 20.0]);
+        app.insert_resource(AirPath::over(&path, FLYING_ALTITUDE))
+            .insert_resource(path)
+            .add_system(move_targets);
+        app
+    }
+
+    fn spawn_walker(app: &mut App, distance: f32, speed: f32) -> Entity {
+        let target = Target {
+            speed,
+            distance,
+            ..default()
+        };
+        app.world
+            .spawn((target, StatusEffects::default(), Transform::default()))
+            .id()
+    }
+
+    #[test]
+    fn walkers_stop_short_of_blockers() {
+        let mut app = walking_app();
+        // Fast enough that any time passing at all takes it up to the blocker
+        let walker = spawn_walker(&mut app, 0.0, 1e9);
+        let blocker = app.world.spawn(PathBlocker { distance: 10.0 }).id();
+
+        // The first update has no time passing
+        app.update();
+        app.update();
+
+        let target = app.world.get::<Target>(walker).unwrap();
+        assert_eq!(target.distance, 10.0 - BLOCKER_GAP);
+        assert_eq!(target.blocked_by, Some(blocker));
+    }
+
+    #[test]
+    fn blockers_placed_right_ahead_never_push_walkers_back() {
+        let mut app = walking_app();
+        let walker = spawn_walker(&mut app, 5.0, 1.0);
+        let blocker = app.world.spawn(PathBlocker { distance: 5.2 }).id();
+
+        app.update();
+        app.update();
+
+        let target = app.world.get::<Target>(walker).unwrap();
+        assert_eq!(target.distance, 5.0);
+        assert_eq!(target.blocked_by, Some(blocker));
     }
 }
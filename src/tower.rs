@@ -1,10 +1,16 @@
-use crate::{Bullet, GameAssets, GameState, Lifetime, PhysicsBundle, Player, Target};
+use crate::{
+    has_line_of_sight, AirPath, Artillery, ArtilleryEvent, Barracks, Beam, Bullet, ChainLightning,
+    ChainLightningEvent, Currency, Explosive, Funds, GameAssets, GameState, GridEntry, Health,
+    Hitscan, HitscanEvent, Homing, LineOfSight, Movement, Obstacle, PhysicsBundle, Pierce, Player,
+    PoolKind, ProjectilePool, Ricochet, Target, TargetGrid, TargetPath, TowerStats,
+};
 use bevy::ecs::query::QuerySingleError;
 use bevy::prelude::shape::Capsule;
 use bevy::prelude::*;
 use bevy::utils::FloatOrd;
 use bevy_inspector_egui::Inspectable;
 use bevy_mod_picking::*;
+use bevy_rapier3d::prelude::RapierContext;
 
 #[derive(Component)]
 pub struct TowerUIRoot;
@@ -13,6 +19,9 @@ pub struct TowerPlugin;
 impl Plugin for TowerPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Tower>()
+            .register_type::<CanTarget>()
+            .register_type::<TargetingMode>()
+            .add_startup_system(tower_picking_asset_loading)
             .add_system_set(SystemSet::on_enter(GameState::Gameplay).with_system(spawn_tower_bases))
             .add_system_set(
                 SystemSet::on_update(GameState::Gameplay)
@@ -30,18 +39,171 @@ pub struct Tower {
     pub shooting_timer: Timer,
     pub bullet_offset: Vec3,
     pub range: f32,
+    pub level: u32,
+}
+
+/// Highest level a tower can be upgraded to.
+pub const MAX_TOWER_LEVEL: u32 = 3;
+/// Range multiplier gained with every upgrade.
+const RANGE_PER_LEVEL: f32 = 1.2;
+/// Time between shots multiplier with every upgrade.
+const FIRE_INTERVAL_PER_LEVEL: f32 = 0.85;
+/// Beam damage multiplier with every upgrade, beams never wait on the fire interval.
+const BEAM_DPS_PER_LEVEL: f32 = 1.3;
+
+/// What a beam doing `dps` damage per second does after one more upgrade.
+pub fn upgraded_beam_dps(dps: f32) -> f32 {
+    dps * BEAM_DPS_PER_LEVEL
+}
+
+impl Tower {
+    pub fn can_upgrade(&self) -> bool {
+        self.level < MAX_TOWER_LEVEL
+    }
+
+    pub fn upgraded_range(&self) -> f32 {
+        self.range * RANGE_PER_LEVEL
+    }
+
+    pub fn fire_interval(&self) -> f32 {
+        self.shooting_timer.duration().as_secs_f32()
+    }
+
+    pub fn upgraded_fire_interval(&self) -> f32 {
+        self.fire_interval() * FIRE_INTERVAL_PER_LEVEL
+    }
+
+    /// Levels the tower up, `beam` being its beam if it fires one.
+    pub fn upgrade(&mut self, beam: Option<&mut Beam>) {
+        self.level += 1;
+        self.range = self.upgraded_range();
+        match beam {
+            // Beams fire continuously, so they burn hotter instead of firing sooner
+            Some(beam) => {
+                beam.base_dps = upgraded_beam_dps(beam.base_dps);
+                beam.ramp_per_second = upgraded_beam_dps(beam.ramp_per_second);
+                beam.max_dps = upgraded_beam_dps(beam.max_dps);
+            }
+            None => {
+                let interval = self.upgraded_fire_interval();
+                self.shooting_timer
+                    .set_duration(std::time::Duration::from_secs_f32(interval));
+            }
+        }
+    }
+}
+
+/// Which of the targets in reach a tower shoots at.
+#[derive(Reflect, Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum TargetingMode {
+    /// Furthest along its route, the closest to leaking
+    First,
+    /// Least far along its route
+    Last,
+    #[default]
+    Closest,
+    Strongest,
+    Weakest,
+}
+
+impl TargetingMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TargetingMode::First => "First",
+            TargetingMode::Last => "Last",
+            TargetingMode::Closest => "Closest",
+            TargetingMode::Strongest => "Strongest",
+            TargetingMode::Weakest => "Weakest",
+        }
+    }
+
+    /// The mode after this one, for cycling through them from the tower panel.
+    pub fn next(&self) -> Self {
+        match self {
+            TargetingMode::First => TargetingMode::Last,
+            TargetingMode::Last => TargetingMode::Closest,
+            TargetingMode::Closest => TargetingMode::Strongest,
+            TargetingMode::Strongest => TargetingMode::Weakest,
+            TargetingMode::Weakest => TargetingMode::First,
+        }
+    }
+
+    /// How much a tower in this mode wants to shoot a target, the highest score is picked.
+    fn score(&self, distance_to_tower: f32, remaining_route: f32, health: f32) -> f32 {
+        match self {
+            TargetingMode::First => -remaining_route,
+            TargetingMode::Last => remaining_route,
+            TargetingMode::Closest => -distance_to_tower,
+            TargetingMode::Strongest => health,
+            TargetingMode::Weakest => -health,
+        }
+    }
+}
+
+/// The candidate a tower at `origin` in `mode` should shoot at.
+pub fn pick_target<'a>(
+    mode: TargetingMode,
+    origin: Vec3,
+    candidates: impl Iterator<Item = &'a GridEntry>,
+    targets: &Query<(&Target, &Health)>,
+    path: &TargetPath,
+    air_path: &AirPath,
+) -> Option<&'a GridEntry> {
+    candidates
+        .filter_map(|entry| {
+            let (target, health) = targets.get(entry.entity).ok()?;
+            let remaining_route = target.movement.route(path, air_path).length() - target.distance;
+            let score = mode.score(
+                entry.position.distance(origin),
+                remaining_route,
+                health.value,
+            );
+            Some((entry, score))
+        })
+        .max_by_key(|(_, score)| FloatOrd(*score))
+        .map(|(entry, _)| entry)
+}
+
+/// An empty spot a tower can be built on.
+#[derive(Component)]
+pub struct TowerBase;
+
+/// Invisible shape that makes built towers hoverable and selectable.
+#[derive(Resource)]
+pub struct TowerPickingAssets {
+    mesh: Handle<Mesh>,
+    hidden_material: Handle<StandardMaterial>,
+    highlight_material: Handle<StandardMaterial>,
+}
+
+/// Which kinds of target a tower is able to shoot at.
+#[derive(Reflect, Component, Default, Clone, Copy)]
+#[reflect(Component)]
+pub struct CanTarget {
+    pub ground: bool,
+    pub air: bool,
+}
+
+impl CanTarget {
+    pub fn allows(&self, movement: Movement) -> bool {
+        match movement {
+            Movement::Ground => self.ground,
+            Movement::Flying => self.air,
+        }
+    }
 }
 
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct TowerButtonAttributes {
-    pub cost: u32,
+    pub cost: Funds,
 }
 
 #[derive(Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct TowerButtonState {
-    cost: u32,
+    cost: Funds,
     affordable: bool,
 }
 
@@ -50,10 +212,30 @@ pub enum TowerType {
     TowerA,
     TowerB,
     TowerC,
+    TowerD,
+    TowerE,
+    TowerF,
+    TowerG,
+    TowerH,
+    TowerI,
 }
 
 impl TowerType {
-    fn get_tower(&self, assets: &GameAssets) -> (Handle<Scene>, Tower) {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TowerType::TowerA => "Arrow",
+            TowerType::TowerB => "Cannon",
+            TowerType::TowerC => "Anti-air",
+            TowerType::TowerD => "Ricochet",
+            TowerType::TowerE => "Tesla",
+            TowerType::TowerF => "Sniper",
+            TowerType::TowerG => "Laser",
+            TowerType::TowerH => "Catapult",
+            TowerType::TowerI => "Barracks",
+        }
+    }
+
+    pub fn get_tower(&self, assets: &GameAssets) -> (Handle<Scene>, Tower) {
         match self {
             TowerType::TowerA => (
                 assets.tower_a_scene.clone(),
@@ -61,6 +243,7 @@ impl TowerType {
                     shooting_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
                     bullet_offset: Vec3::new(0.0, 0.6, 0.0),
                     range: 4.5,
+                    level: 1,
                 },
             ),
             TowerType::TowerB => (
@@ -69,6 +252,7 @@ impl TowerType {
                     shooting_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
                     bullet_offset: Vec3::new(0.0, 0.6, 0.0),
                     range: 4.5,
+                    level: 1,
                 },
             ),
             TowerType::TowerC => (
@@ -77,55 +261,357 @@ impl TowerType {
                     shooting_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
                     bullet_offset: Vec3::new(0.0, 0.6, 0.0),
                     range: 4.5,
+                    level: 1,
+                },
+            ),
+            TowerType::TowerD => (
+                assets.tower_d_scene.clone(),
+                Tower {
+                    shooting_timer: Timer::from_seconds(0.7, TimerMode::Repeating),
+                    bullet_offset: Vec3::new(0.0, 0.6, 0.0),
+                    range: 4.5,
+                    level: 1,
+                },
+            ),
+            TowerType::TowerE => (
+                assets.tower_e_scene.clone(),
+                Tower {
+                    shooting_timer: Timer::from_seconds(1.2, TimerMode::Repeating),
+                    bullet_offset: Vec3::new(0.0, 0.8, 0.0),
+                    range: 4.0,
+                    level: 1,
+                },
+            ),
+            TowerType::TowerF => (
+                assets.tower_f_scene.clone(),
+                Tower {
+                    shooting_timer: Timer::from_seconds(1.5, TimerMode::Repeating),
+                    bullet_offset: Vec3::new(0.0, 0.8, 0.0),
+                    range: 6.0,
+                    level: 1,
+                },
+            ),
+            TowerType::TowerG => (
+                assets.tower_g_scene.clone(),
+                Tower {
+                    // Beams fire continuously rather than on the timer
+                    shooting_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+                    bullet_offset: Vec3::new(0.0, 0.8, 0.0),
+                    range: 3.5,
+                    level: 1,
                 },
             ),
+            TowerType::TowerH => (
+                assets.tower_h_scene.clone(),
+                Tower {
+                    shooting_timer: Timer::from_seconds(3.0, TimerMode::Repeating),
+                    bullet_offset: Vec3::new(0.0, 0.8, 0.0),
+                    range: 8.0,
+                    level: 1,
+                },
+            ),
+            TowerType::TowerI => (
+                assets.tower_i_scene.clone(),
+                Tower {
+                    // Trains a soldier each time this comes round while the squad is short
+                    shooting_timer: Timer::from_seconds(8.0, TimerMode::Repeating),
+                    bullet_offset: Vec3::new(0.0, 0.8, 0.0),
+                    // How far from their post soldiers will go to fight
+                    range: 2.5,
+                    level: 1,
+                },
+            ),
+        }
+    }
+
+    fn can_target(&self) -> CanTarget {
+        match self {
+            TowerType::TowerA
+            | TowerType::TowerD
+            | TowerType::TowerE
+            | TowerType::TowerF
+            | TowerType::TowerG => CanTarget {
+                ground: true,
+                air: true,
+            },
+            TowerType::TowerB | TowerType::TowerH => CanTarget {
+                ground: true,
+                air: false,
+            },
+            TowerType::TowerC => CanTarget {
+                ground: false,
+                air: true,
+            },
+            // Its soldiers do the fighting
+            TowerType::TowerI => CanTarget {
+                ground: false,
+                air: false,
+            },
         }
     }
 
+    /// Artillery lobs shells over anything in the way, every other tower needs a clear view.
+    fn needs_line_of_sight(&self) -> bool {
+        !matches!(self, TowerType::TowerH | TowerType::TowerI)
+    }
+
+    /// What the tower's timer is for, shown with its interval in the tower panel.
+    pub fn interval_label(&self) -> &'static str {
+        match self {
+            TowerType::TowerI => "Trains a soldier every",
+            _ => "Fires every",
+        }
+    }
+
+    /// Upgrades cost the build price again for every level the tower already has.
+    pub fn upgrade_cost(&self, assets: &GameAssets, level: u32) -> Funds {
+        self.get_tower_button(assets).1.cost.scaled(level)
+    }
+
     fn get_tower_button(&self, assets: &GameAssets) -> (Handle<Image>, TowerButtonAttributes) {
         match self {
             TowerType::TowerA => (
                 assets.tower_a_icon.clone(),
-                TowerButtonAttributes { cost: 50 },
+                TowerButtonAttributes {
+                    cost: Funds::gold(50),
+                },
             ),
             TowerType::TowerB => (
                 assets.tower_b_icon.clone(),
-                TowerButtonAttributes { cost: 80 },
+                TowerButtonAttributes {
+                    cost: Funds::gold(80),
+                },
             ),
             TowerType::TowerC => (
                 assets.tower_c_icon.clone(),
-                TowerButtonAttributes { cost: 110 },
+                TowerButtonAttributes {
+                    cost: Funds::gold(110),
+                },
+            ),
+            TowerType::TowerD => (
+                assets.tower_d_icon.clone(),
+                TowerButtonAttributes {
+                    cost: Funds::gold(90),
+                },
+            ),
+            TowerType::TowerE => (
+                assets.tower_e_icon.clone(),
+                TowerButtonAttributes {
+                    cost: Funds::new(&[(Currency::Gold, 130), (Currency::Crystals, 1)]),
+                },
+            ),
+            TowerType::TowerF => (
+                assets.tower_f_icon.clone(),
+                TowerButtonAttributes {
+                    cost: Funds::new(&[(Currency::Gold, 120), (Currency::Crystals, 1)]),
+                },
+            ),
+            TowerType::TowerG => (
+                assets.tower_g_icon.clone(),
+                TowerButtonAttributes {
+                    cost: Funds::new(&[(Currency::Gold, 140), (Currency::Crystals, 2)]),
+                },
+            ),
+            TowerType::TowerH => (
+                assets.tower_h_icon.clone(),
+                TowerButtonAttributes {
+                    cost: Funds::gold(150),
+                },
+            ),
+            TowerType::TowerI => (
+                assets.tower_i_icon.clone(),
+                TowerButtonAttributes {
+                    cost: Funds::gold(100),
+                },
             ),
         }
     }
 
-    fn get_bullet(&self, direction: Vec3, assets: &GameAssets) -> (Handle<Scene>, Bullet) {
+    /// Anti-air bullets chase their target, everything else flies straight
+    fn get_homing(&self, target: Entity) -> Option<Homing> {
         match self {
-            TowerType::TowerA => (
+            TowerType::TowerC => Some(Homing {
+                target: Some(target),
+                turn_rate: 4.0,
+                retarget_range: 3.0,
+                can_target: self.can_target(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Ground-only shells splash everything around the point they were aimed at
+    fn get_explosive(&self, target_position: Vec3) -> Option<Explosive> {
+        match self {
+            TowerType::TowerB => Some(Explosive {
+                radius: 1.5,
+                damage: 2.0,
+                falloff: 0.75,
+                detonation_point: Some(target_position),
+                can_target: self.can_target(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Arrows punch through a couple of targets before stopping
+    fn get_pierce(&self) -> Option<Pierce> {
+        match self {
+            TowerType::TowerA => Some(Pierce {
+                remaining: 2,
+                hit: Vec::new(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn get_ricochet(&self) -> Option<Ricochet> {
+        match self {
+            TowerType::TowerD => Some(Ricochet {
+                remaining: 3,
+                range: 2.5,
+                damage_falloff: 0.25,
+                hit: Vec::new(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Lightning towers hit instantly and never fire a bullet
+    fn get_chain_lightning(&self) -> Option<ChainLightning> {
+        match self {
+            TowerType::TowerE => Some(ChainLightning {
+                damage: 2.0,
+                jumps: 4,
+                jump_radius: 2.0,
+                damage_falloff: 0.2,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Sniper towers hit the first target along the line of fire the instant they shoot
+    fn get_hitscan(&self) -> Option<Hitscan> {
+        match self {
+            TowerType::TowerF => Some(Hitscan {
+                damage: 3.0,
+                crit_chance: 0.2,
+                crit_multiplier: 2.5,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Laser towers burn one target continuously instead of shooting
+    fn get_beam(&self) -> Option<Beam> {
+        match self {
+            TowerType::TowerG => Some(Beam {
+                target: None,
+                held_for: 0.0,
+                base_dps: 0.5,
+                ramp_per_second: 0.5,
+                max_dps: 3.0,
+                visual: None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Mortars lob slow heavy shells over everything in the way
+    fn get_artillery(&self) -> Option<Artillery> {
+        match self {
+            TowerType::TowerH => Some(Artillery {
+                launch_angle: 60_f32.to_radians(),
+                gravity: 9.8,
+                min_range: 2.5,
+                explosive: Explosive {
+                    radius: 2.0,
+                    damage: 3.0,
+                    falloff: 0.5,
+                    detonation_point: None,
+                    can_target: self.can_target(),
+                },
+            }),
+            _ => None,
+        }
+    }
+
+    /// Barracks never shoot, they train soldiers to hold the path instead
+    fn get_barracks(&self) -> Option<Barracks> {
+        match self {
+            TowerType::TowerI => Some(Barracks::default()),
+            _ => None,
+        }
+    }
+
+    fn get_bullet(
+        &self,
+        direction: Vec3,
+        range: f32,
+        assets: &GameAssets,
+    ) -> Option<(Handle<Scene>, Bullet)> {
+        match self {
+            TowerType::TowerA => Some((
                 assets.tower_a_bullet_scene.clone(),
                 Bullet {
                     direction,
                     speed: 3.5,
+                    damage: 1.0,
+                    range,
+                    travelled: 0.0,
+                    source: None,
                 },
-            ),
-            TowerType::TowerB => (
+            )),
+            TowerType::TowerB => Some((
                 assets.tower_b_bullet_scene.clone(),
                 Bullet {
                     direction,
                     speed: 3.5,
+                    damage: 1.0,
+                    range,
+                    travelled: 0.0,
+                    source: None,
                 },
-            ),
-            TowerType::TowerC => (
+            )),
+            TowerType::TowerC => Some((
                 assets.tower_c_bullet_scene.clone(),
                 Bullet {
                     direction,
                     speed: 3.5,
+                    damage: 1.0,
+                    range,
+                    travelled: 0.0,
+                    source: None,
                 },
-            ),
+            )),
+            TowerType::TowerD => Some((
+                assets.tower_d_bullet_scene.clone(),
+                Bullet {
+                    direction,
+                    speed: 4.0,
+                    damage: 1.5,
+                    range,
+                    travelled: 0.0,
+                    source: None,
+                },
+            )),
+            _ => None,
         }
     }
 }
 
+fn tower_picking_asset_loading(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(TowerPickingAssets {
+        mesh: meshes.add(Capsule::default().into()),
+        hidden_material: materials.add(Color::NONE.into()),
+        highlight_material: materials.add(Color::rgba(0.9, 0.9, 0.3, 0.3).into()),
+    });
+}
+
 fn spawn_tower_bases(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -145,6 +631,7 @@ fn spawn_tower_bases(
                 x, 0.0, z,
             )))
             .insert(Name::new("Tower base"))
+            .insert(TowerBase)
             .insert(meshes.add(Capsule::default().into()))
             .insert(default_collider_color.clone())
             .insert(Highlighting {
@@ -166,8 +653,9 @@ fn spawn_tower_bases(
 
 fn create_ui_on_selection(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
     game_assets: Res<GameAssets>,
-    selections: Query<&Selection>,
+    selections: Query<&Selection, With<TowerBase>>,
     root: Query<Entity, With<TowerUIRoot>>,
 ) {
     let at_least_one_selected = selections.iter().any(|selection| selection.selected());
@@ -180,15 +668,26 @@ fn create_ui_on_selection(
         // No root exists
         Err(QuerySingleError::NoEntities(..)) => {
             if at_least_one_selected {
-                create_ui(commands, &game_assets);
+                let font = asset_server.load("fonts/Merriweather-Regular.ttf");
+                create_ui(commands, &game_assets, font);
             }
         }
         _ => unreachable!("Too manage ui tower roots!"),
     }
 }
 
-fn create_ui(mut commands: Commands, game_assets: &GameAssets) {
-    let tower_types = [TowerType::TowerA, TowerType::TowerB, TowerType::TowerC];
+fn create_ui(mut commands: Commands, game_assets: &GameAssets, font: Handle<Font>) {
+    let tower_types = [
+        TowerType::TowerA,
+        TowerType::TowerB,
+        TowerType::TowerC,
+        TowerType::TowerD,
+        TowerType::TowerE,
+        TowerType::TowerF,
+        TowerType::TowerG,
+        TowerType::TowerH,
+        TowerType::TowerI,
+    ];
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -208,12 +707,28 @@ fn create_ui(mut commands: Commands, game_assets: &GameAssets) {
                         style: Style {
                             size: Size::new(Val::Percent(15.0 * 9.0 / 16.0), Val::Percent(15.0)),
                             align_self: AlignSelf::FlexEnd,
-                            margin: UiRect::all(Val::Percent(2.0)),
+                            margin: UiRect::all(Val::Percent(1.0)),
+                            // Cost label along the bottom of the icon
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::FlexEnd,
                             ..default()
                         },
                         image: tower_icon.into(),
                         ..default()
                     })
+                    .with_children(|commands| {
+                        commands.spawn(
+                            TextBundle::from_section(
+                                tower_attributes.cost.to_string(),
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 14.0,
+                                    color: Color::BLACK,
+                                },
+                            )
+                            .with_text_alignment(TextAlignment::CENTER),
+                        );
+                    })
                     .insert(TowerButtonState {
                         cost: tower_attributes.cost,
                         // Maintained in a different system after this system
@@ -230,7 +745,7 @@ fn grey_tower_buttons(
 ) {
     let player = player.single();
     for (mut tint, mut state) in &mut buttons {
-        if player.money > state.cost {
+        if player.funds.can_afford(&state.cost) {
             state.affordable = true;
             *tint = Color::WHITE.into();
         } else {
@@ -243,8 +758,9 @@ fn grey_tower_buttons(
 fn tower_button_clicked(
     mut commands: Commands,
     interactions: Query<(&Interaction, &TowerType, &TowerButtonState), Changed<Interaction>>,
-    selection: Query<(Entity, &Selection, &Transform)>,
+    selection: Query<(Entity, &Selection, &Transform), With<TowerBase>>,
     assets: Res<GameAssets>,
+    picking_assets: Res<TowerPickingAssets>,
     mut player: Query<&mut Player>,
 ) {
     let mut player = player.single_mut();
@@ -252,10 +768,15 @@ fn tower_button_clicked(
         if matches!(interaction, Interaction::Clicked) {
             for (entity, selection, transform) in &selection {
                 if selection.selected() {
-                    if player.money >= button_state.cost {
-                        player.money -= button_state.cost;
+                    if player.funds.spend(&button_state.cost) {
                         commands.entity(entity).despawn_recursive();
-                        spawn_tower(&mut commands, &assets, transform.translation, tower_type);
+                        spawn_tower(
+                            &mut commands,
+                            &assets,
+                            &picking_assets,
+                            transform.translation,
+                            tower_type,
+                        );
                     }
                 }
             }
@@ -266,66 +787,171 @@ fn tower_button_clicked(
 fn spawn_tower(
     commands: &mut Commands,
     assets: &GameAssets,
+    picking_assets: &TowerPickingAssets,
     position: Vec3,
     tower_type: &TowerType,
 ) -> Entity {
     let (model, tower) = tower_type.get_tower(assets);
-    commands
-        .spawn(SpatialBundle::from_transform(Transform::from_translation(
-            position,
-        )))
+    let mut tower_commands = commands.spawn(SpatialBundle::from_transform(
+        Transform::from_translation(position),
+    ));
+    tower_commands
         .insert(Name::new("Tower_1"))
         .insert(*tower_type)
         .insert(tower)
+        .insert(tower_type.can_target())
+        .insert(TargetingMode::default())
+        .insert(TowerStats::default())
+        .insert(picking_assets.mesh.clone())
+        .insert(picking_assets.hidden_material.clone())
+        .insert(Highlighting {
+            initial: picking_assets.hidden_material.clone(),
+            hovered: Some(picking_assets.highlight_material.clone()),
+            pressed: Some(picking_assets.highlight_material.clone()),
+            selected: Some(picking_assets.highlight_material.clone()),
+        })
+        .insert(PickableBundle::default())
         .with_children(|commands| {
             commands.spawn(SceneBundle {
                 scene: model,
                 transform: Transform::from_xyz(0.0, -0.5, 0.0),
                 ..default()
             });
-        })
-        .id()
+        });
+    if let Some(beam) = tower_type.get_beam() {
+        tower_commands.insert(beam);
+    }
+    if let Some(barracks) = tower_type.get_barracks() {
+        tower_commands.insert(barracks);
+    }
+    if tower_type.needs_line_of_sight() {
+        tower_commands.insert(LineOfSight);
+    }
+    tower_commands.id()
 }
 
 fn tower_shooting(
     mut commands: Commands,
-    mut towers: Query<(Entity, &mut Tower, &TowerType, &GlobalTransform)>,
-    targets: Query<&GlobalTransform, With<Target>>,
+    mut towers: Query<
+        (
+            Entity,
+            &mut Tower,
+            &TowerType,
+            &CanTarget,
+            &TargetingMode,
+            &GlobalTransform,
+            Option<&LineOfSight>,
+        ),
+        Without<Beam>,
+    >,
+    targets: Query<(&Target, &Health)>,
+    path: Res<TargetPath>,
+    air_path: Res<AirPath>,
+    grid: Res<TargetGrid>,
+    rapier_context: Res<RapierContext>,
+    obstacles: Query<(), With<Obstacle>>,
     assets: Res<GameAssets>,
     time: Res<Time>,
+    mut lightning_events: EventWriter<ChainLightningEvent>,
+    mut hitscan_events: EventWriter<HitscanEvent>,
+    mut artillery_events: EventWriter<ArtilleryEvent>,
+    mut pool: ResMut<ProjectilePool>,
 ) {
-    for (tower_ent, mut tower, tower_type, transform) in &mut towers {
+    for (tower_entity, mut tower, tower_type, can_target, targeting, transform, line_of_sight) in
+        &mut towers
+    {
         tower.shooting_timer.tick(time.delta());
         if tower.shooting_timer.just_finished() {
             let bullet_spawn = transform.translation() + tower.bullet_offset;
+            let artillery = tower_type.get_artillery();
+            let min_range = artillery.map_or(0.0, |artillery| artillery.min_range);
 
-            let direction = targets
-                .iter()
-                .filter(|target_transform| {
-                    Vec3::distance(target_transform.translation(), bullet_spawn) < tower.range
-                })
-                .min_by_key(|target_transform| {
-                    FloatOrd(Vec3::distance(target_transform.translation(), bullet_spawn))
-                })
-                .map(|closest_target| closest_target.translation() - bullet_spawn);
-
-            if let Some(direction) = direction {
-                let (model, bullet) = tower_type.get_bullet(direction, &assets);
-                commands.entity(tower_ent).with_children(|commands| {
-                    commands
-                        .spawn(SceneBundle {
-                            scene: model,
-                            transform: Transform::from_translation(tower.bullet_offset)
-                                .looking_at(bullet.direction, Vec3::Y),
-                            ..Default::default()
-                        })
-                        .insert(Lifetime {
-                            timer: Timer::from_seconds(1000.5, TimerMode::Once),
-                        })
-                        .insert(bullet)
-                        .insert(PhysicsBundle::moving_entity(Vec3::new(0.2, 0.2, 0.2)))
-                        .insert(Name::new("Bullet"));
-                });
+            let in_reach = grid.within(bullet_spawn, tower.range).filter(|entry| {
+                can_target.allows(entry.movement)
+                    && entry.position.distance(bullet_spawn) >= min_range
+                    && (line_of_sight.is_none()
+                        || has_line_of_sight(
+                            &rapier_context,
+                            &obstacles,
+                            bullet_spawn,
+                            entry.position,
+                        ))
+            });
+            let chosen = pick_target(
+                *targeting,
+                bullet_spawn,
+                in_reach,
+                &targets,
+                &path,
+                &air_path,
+            );
+
+            if let Some(chosen) = chosen {
+                let (target, target_position) = (chosen.entity, chosen.position);
+                if let Some(lightning) = tower_type.get_chain_lightning() {
+                    lightning_events.send(ChainLightningEvent {
+                        tower: tower_entity,
+                        origin: bullet_spawn,
+                        target,
+                        lightning,
+                    });
+                    continue;
+                }
+
+                if let Some(artillery) = artillery {
+                    artillery_events.send(ArtilleryEvent {
+                        tower: tower_entity,
+                        origin: bullet_spawn,
+                        target,
+                        artillery,
+                    });
+                    continue;
+                }
+
+                let direction = target_position - bullet_spawn;
+                if let Some(hitscan) = tower_type.get_hitscan() {
+                    hitscan_events.send(HitscanEvent {
+                        tower: tower_entity,
+                        origin: bullet_spawn,
+                        direction,
+                        range: tower.range,
+                        hitscan,
+                        can_target: *can_target,
+                    });
+                    continue;
+                }
+
+                // Let misses fly on a little past the edge of the tower's range
+                let Some((model, mut bullet)) =
+                    tower_type.get_bullet(direction, tower.range * 1.5, &assets)
+                else {
+                    continue;
+                };
+                bullet.source = Some(tower_entity);
+                let homing = tower_type.get_homing(target);
+                let explosive = tower_type.get_explosive(target_position);
+                let pierce = tower_type.get_pierce();
+                let ricochet = tower_type.get_ricochet();
+                let transform = Transform::from_translation(bullet_spawn)
+                    .looking_at(bullet_spawn + bullet.direction, Vec3::Y);
+                let entity = pool.acquire(&mut commands, &PoolKind::Scene(model), transform);
+                let mut bullet_commands = commands.entity(entity);
+                bullet_commands
+                    .insert(bullet)
+                    .insert(PhysicsBundle::moving_entity(Vec3::new(0.2, 0.2, 0.2)))
+                    .insert(Name::new("Bullet"));
+                if let Some(homing) = homing {
+                    bullet_commands.insert(homing);
+                }
+                if let Some(explosive) = explosive {
+                    bullet_commands.insert(explosive);
+                }
+                if let Some(pierce) = pierce {
+                    bullet_commands.insert(pierce);
+                }
+                if let Some(ricochet) = ricochet {
+                    bullet_commands.insert(ricochet);
+                }
             }
         }
     }
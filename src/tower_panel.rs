@@ -0,0 +1,295 @@
+use crate::{
+    upgraded_beam_dps, Beam, Funds, GameAssets, GameState, Player, TargetingMode, Tower,
+    TowerStats, TowerType,
+};
+use bevy::prelude::*;
+use bevy_mod_picking::Selection;
+
+/// Panel for the selected built tower, rebuilt when the selection or its level changes.
+#[derive(Component)]
+pub struct TowerPanelRoot {
+    tower: Entity,
+    level: u32,
+}
+
+#[derive(Component)]
+pub struct UpgradeButton {
+    pub tower: Entity,
+    cost: Funds,
+}
+
+/// Cycles the selected tower through the targeting modes.
+#[derive(Component)]
+pub struct TargetingButton {
+    tower: Entity,
+}
+
+/// Panel text that changes while the panel is open, refreshed every frame.
+#[derive(Component, Clone, Copy)]
+pub enum LiveStat {
+    Targeting,
+    Kills,
+    DamageDealt,
+    Dps,
+}
+
+pub struct TowerPanelPlugin;
+
+impl Plugin for TowerPanelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(tower_panel_on_selection)
+                .with_system(grey_upgrade_button.after(tower_panel_on_selection))
+                .with_system(upgrade_button_clicked)
+                .with_system(targeting_button_clicked)
+                .with_system(update_live_stats.after(tower_panel_on_selection)),
+        );
+    }
+}
+
+fn tower_panel_on_selection(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    towers: Query<(Entity, &Tower, &TowerType, Option<&Beam>, &Selection)>,
+    panels: Query<(Entity, &TowerPanelRoot)>,
+) {
+    let selected = towers
+        .iter()
+        .find(|(_, _, _, _, selection)| selection.selected());
+
+    let mut up_to_date = false;
+    for (panel_entity, panel) in &panels {
+        let showing_selected = selected.is_some_and(|(tower_entity, tower, _, _, _)| {
+            tower_entity == panel.tower && tower.level == panel.level
+        });
+        if showing_selected {
+            up_to_date = true;
+        } else {
+            commands.entity(panel_entity).despawn_recursive();
+        }
+    }
+
+    if up_to_date {
+        return;
+    }
+    if let Some((tower_entity, tower, tower_type, beam, _)) = selected {
+        let font = asset_server.load("fonts/Merriweather-Regular.ttf");
+        spawn_tower_panel(
+            &mut commands,
+            font,
+            &game_assets,
+            tower_entity,
+            tower,
+            tower_type,
+            beam,
+        );
+    }
+}
+
+fn spawn_tower_panel(
+    commands: &mut Commands,
+    font: Handle<Font>,
+    game_assets: &GameAssets,
+    tower_entity: Entity,
+    tower: &Tower,
+    tower_type: &TowerType,
+    beam: Option<&Beam>,
+) {
+    let text_style = TextStyle {
+        font,
+        font_size: 24.0,
+        color: Color::WHITE,
+    };
+
+    let mut lines = vec![format!("{} tower", tower_type.name())];
+    if tower.can_upgrade() {
+        lines.push(format!("Level {} -> {}", tower.level, tower.level + 1));
+        lines.push(format!(
+            "Range {:.1} -> {:.1}",
+            tower.range,
+            tower.upgraded_range()
+        ));
+        // Beams fire continuously, upgrades make them burn hotter instead
+        lines.push(match beam {
+            Some(beam) => format!(
+                "Beam {:.1}-{:.1} dps -> {:.1}-{:.1} dps",
+                beam.base_dps,
+                beam.max_dps,
+                upgraded_beam_dps(beam.base_dps),
+                upgraded_beam_dps(beam.max_dps)
+            ),
+            None => format!(
+                "{} {:.2}s -> {:.2}s",
+                tower_type.interval_label(),
+                tower.fire_interval(),
+                tower.upgraded_fire_interval()
+            ),
+        });
+    } else {
+        lines.push(format!("Level {} (max)", tower.level));
+        lines.push(format!("Range {:.1}", tower.range));
+        lines.push(match beam {
+            Some(beam) => format!("Beam {:.1}-{:.1} dps", beam.base_dps, beam.max_dps),
+            None => format!(
+                "{} {:.2}s",
+                tower_type.interval_label(),
+                tower.fire_interval()
+            ),
+        });
+    }
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Percent(1.0),
+                    // Below the path item bar
+                    top: Val::Percent(16.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .insert(TowerPanelRoot {
+            tower: tower_entity,
+            level: tower.level,
+        })
+        .insert(Name::new("Tower Panel"))
+        .with_children(|commands| {
+            for line in lines {
+                commands.spawn(TextBundle::from_section(line, text_style.clone()));
+            }
+            // Filled in by update_live_stats
+            for stat in [LiveStat::Kills, LiveStat::DamageDealt, LiveStat::Dps] {
+                commands
+                    .spawn(TextBundle::from_section("", text_style.clone()))
+                    .insert(stat);
+            }
+
+            commands
+                .spawn(ButtonBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(8.0)),
+                        padding: UiRect::all(Val::Px(6.0)),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::WHITE.into(),
+                    ..default()
+                })
+                .insert(TargetingButton {
+                    tower: tower_entity,
+                })
+                .with_children(|commands| {
+                    commands
+                        .spawn(TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                color: Color::BLACK,
+                                ..text_style.clone()
+                            },
+                        ))
+                        .insert(LiveStat::Targeting);
+                });
+
+            if tower.can_upgrade() {
+                let cost = tower_type.upgrade_cost(game_assets, tower.level);
+                commands
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            margin: UiRect::top(Val::Px(8.0)),
+                            padding: UiRect::all(Val::Px(6.0)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(UpgradeButton {
+                        tower: tower_entity,
+                        cost: cost.clone(),
+                    })
+                    .with_children(|commands| {
+                        commands.spawn(TextBundle::from_section(
+                            format!("Upgrade ({})", cost),
+                            TextStyle {
+                                color: Color::BLACK,
+                                ..text_style.clone()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn grey_upgrade_button(
+    mut buttons: Query<(&mut BackgroundColor, &UpgradeButton)>,
+    player: Query<&Player>,
+) {
+    let player = player.single();
+    for (mut tint, button) in &mut buttons {
+        *tint = if player.funds.can_afford(&button.cost) {
+            Color::WHITE.into()
+        } else {
+            Color::GRAY.into()
+        };
+    }
+}
+
+fn upgrade_button_clicked(
+    interactions: Query<(&Interaction, &UpgradeButton), Changed<Interaction>>,
+    mut towers: Query<(&mut Tower, Option<&mut Beam>)>,
+    mut player: Query<&mut Player>,
+) {
+    let mut player = player.single_mut();
+    for (interaction, button) in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            if let Ok((mut tower, beam)) = towers.get_mut(button.tower) {
+                if player.funds.spend(&button.cost) {
+                    tower.upgrade(beam.map(Mut::into_inner));
+                }
+            }
+        }
+    }
+}
+
+fn targeting_button_clicked(
+    interactions: Query<(&Interaction, &TargetingButton), Changed<Interaction>>,
+    mut towers: Query<&mut TargetingMode>,
+) {
+    for (interaction, button) in &interactions {
+        if matches!(interaction, Interaction::Clicked) {
+            if let Ok(mut targeting) = towers.get_mut(button.tower) {
+                *targeting = targeting.next();
+            }
+        }
+    }
+}
+
+fn update_live_stats(
+    panel: Query<&TowerPanelRoot>,
+    towers: Query<(&TowerStats, &TargetingMode)>,
+    mut texts: Query<(&LiveStat, &mut Text)>,
+) {
+    let Ok(panel) = panel.get_single() else {
+        return;
+    };
+    let Ok((stats, targeting)) = towers.get(panel.tower) else {
+        return;
+    };
+
+    for (stat, mut text) in &mut texts {
+        text.sections[0].value = match stat {
+            LiveStat::Targeting => format!("Targeting: {}", targeting.name()),
+            LiveStat::Kills => format!("Kills: {}", stats.kills),
+            LiveStat::DamageDealt => format!("Damage dealt: {:.0}", stats.damage_dealt),
+            LiveStat::Dps => format!("DPS: {:.1}", stats.dps()),
+        };
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{GameState, TargetDeathEvent};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// Seconds of damage averaged over for a tower's DPS.
+const DPS_WINDOW: f32 = 5.0;
+
+/// Running totals of what a tower has done, fed from the damage pipeline.
+#[derive(Component, Default)]
+pub struct TowerStats {
+    pub kills: u32,
+    pub damage_dealt: f32,
+    /// Damage dealt within the DPS window, oldest first, with when it was dealt
+    recent_damage: VecDeque<(f32, f32)>,
+}
+
+impl TowerStats {
+    /// Adds damage a target actually took from this tower at `now`.
+    pub fn record_damage(&mut self, now: f32, amount: f32) {
+        self.damage_dealt += amount;
+        self.recent_damage.push_back((now, amount));
+    }
+
+    /// Damage per second averaged over the last few seconds.
+    pub fn dps(&self) -> f32 {
+        self.recent_damage
+            .iter()
+            .map(|(_, amount)| amount)
+            .sum::<f32>()
+            / DPS_WINDOW
+    }
+}
+
+pub struct TowerStatsPlugin;
+
+impl Plugin for TowerStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(expire_recent_damage)
+                .with_system(record_tower_kills),
+        );
+    }
+}
+
+/// Drops damage that has aged out of the DPS window. Damage is recorded by
+/// `apply_damage`, once shields and overkill are taken off.
+fn expire_recent_damage(mut towers: Query<&mut TowerStats>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    for mut stats in &mut towers {
+        while stats
+            .recent_damage
+            .front()
+            .is_some_and(|(dealt_at, _)| now - dealt_at > DPS_WINDOW)
+        {
+            stats.recent_damage.pop_front();
+        }
+    }
+}
+
+fn record_tower_kills(
+    mut towers: Query<&mut TowerStats>,
+    mut death_events: EventReader<TargetDeathEvent>,
+) {
+    for death in death_events.iter() {
+        let Some(killer) = death.killer else {
+            continue;
+        };
+        if let Ok(mut stats) = towers.get_mut(killer) {
+            stats.kills += 1;
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    spawn_target, AirPath, EnemyType, GameAssets, GameState, Target, TargetPath,
+    TargetPickingAssets,
+};
+use bevy::prelude::*;
+
+/// Seconds to build before a wave starts, counted once the previous one is cleared.
+const WAVE_COUNTDOWN: f32 = 10.0;
+/// Gap along the route between the queued enemies of a wave.
+const ENEMY_SPACING: f32 = 2.0;
+const WAVE_COUNT: u32 = 10;
+
+/// A batch of one kind of enemy within a wave.
+#[derive(Clone, Copy, Debug)]
+pub struct WaveGroup {
+    pub enemy_type: EnemyType,
+    pub count: u32,
+}
+
+pub struct Wave {
+    pub groups: Vec<WaveGroup>,
+}
+
+impl Wave {
+    /// Waves grow by two barbarians each, with UFOs joining from the second.
+    fn generate(number: u32) -> Self {
+        let mut groups = vec![WaveGroup {
+            enemy_type: EnemyType::Barbarian,
+            count: 4 + 2 * number,
+        }];
+        if number >= 2 {
+            groups.push(WaveGroup {
+                enemy_type: EnemyType::Ufo,
+                count: number / 2,
+            });
+        }
+        Wave { groups }
+    }
+
+    pub fn enemy_count(&self) -> u32 {
+        self.groups.iter().map(|group| group.count).sum()
+    }
+}
+
+#[derive(Resource)]
+pub struct Waves {
+    pub waves: Vec<Wave>,
+    /// Waves started so far, 0 before the first
+    pub current: usize,
+    /// Waves whose enemies have all died or leaked
+    pub cleared: usize,
+    /// Time left until the next wave, only ticks while no enemies are left
+    pub countdown: Timer,
+}
+
+impl Default for Waves {
+    fn default() -> Self {
+        Waves {
+            waves: (1..=WAVE_COUNT).map(Wave::generate).collect(),
+            current: 0,
+            cleared: 0,
+            countdown: Timer::from_seconds(WAVE_COUNTDOWN, TimerMode::Once),
+        }
+    }
+}
+
+impl Waves {
+    pub fn total(&self) -> usize {
+        self.waves.len()
+    }
+
+    pub fn next_wave(&self) -> Option<&Wave> {
+        self.waves.get(self.current)
+    }
+
+    /// What calling the next wave right now would pay for the rest of the countdown.
+    pub fn call_early_bonus(&self, bonus_per_second: f32) -> u32 {
+        (self.countdown.remaining_secs() * bonus_per_second).round() as u32
+    }
+
+    /// Starts the next wave on the following update. Only meant for while the
+    /// countdown runs, since the bonus is paid for the time left on it.
+    pub fn call_early(&mut self) {
+        let remaining = self.countdown.remaining();
+        self.countdown.tick(remaining);
+    }
+}
+
+/// Sent once for every wave when the field is next clear, in order.
+pub struct WaveClearedEvent {
+    /// Numbered from 1
+    pub wave: usize,
+}
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Waves>()
+            .add_event::<WaveClearedEvent>()
+            .add_system_set(
+                SystemSet::on_update(GameState::Gameplay)
+                    // Before a new wave is spawned while the field still looks clear
+                    .with_system(detect_cleared_waves.before(advance_waves))
+                    .with_system(advance_waves),
+            );
+    }
+}
+
+fn detect_cleared_waves(
+    mut waves: ResMut<Waves>,
+    targets: Query<(), With<Target>>,
+    mut cleared_events: EventWriter<WaveClearedEvent>,
+) {
+    if !targets.is_empty() || waves.cleared == waves.current {
+        return;
+    }
+    // Waves never overlap, the next only starts once the field is clear
+    cleared_events.send(WaveClearedEvent {
+        wave: waves.current,
+    });
+    waves.cleared = waves.current;
+}
+
+fn advance_waves(
+    mut commands: Commands,
+    mut waves: ResMut<Waves>,
+    targets: Query<(), With<Target>>,
+    game_assets: Res<GameAssets>,
+    picking_assets: Res<TargetPickingAssets>,
+    (path, air_path): (Res<TargetPath>, Res<AirPath>),
+    time: Res<Time>,
+) {
+    if waves.next_wave().is_none() {
+        return;
+    }
+    // Counts down once the field is clear, unless the wave has been called early
+    if targets.is_empty() {
+        waves.countdown.tick(time.delta());
+    }
+    if !waves.countdown.finished() {
+        return;
+    }
+
+    let enemies = waves.waves[waves.current]
+        .groups
+        .iter()
+        .flat_map(|group| std::iter::repeat(group.enemy_type).take(group.count as usize));
+    for (i, enemy_type) in enemies.enumerate() {
+        spawn_target(
+            &mut commands,
+            &game_assets,
+            &picking_assets,
+            &path,
+            &air_path,
+            enemy_type,
+            -ENEMY_SPACING * i as f32,
+        );
+    }
+
+    waves.current += 1;
+    waves.countdown.reset();
+}
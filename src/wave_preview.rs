@@ -0,0 +1,220 @@
+use crate::{Currency, GameAssets, GameState, Level, Player, Target, Waves};
+use bevy::prelude::*;
+
+/// Panel showing what the next wave holds, rebuilt whenever a wave starts.
+#[derive(Component)]
+pub struct WavePreviewRoot {
+    wave: usize,
+}
+
+#[derive(Component)]
+pub struct WaveCountdownText;
+
+/// Starts the next wave straight away for a bonus scaled to the time skipped,
+/// only shown while the countdown runs.
+#[derive(Component)]
+pub struct CallEarlyButton;
+
+#[derive(Component)]
+pub struct CallEarlyText;
+
+pub struct WavePreviewPlugin;
+
+impl Plugin for WavePreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Gameplay)
+                .with_system(wave_preview_on_wave_change)
+                .with_system(update_wave_countdown.after(wave_preview_on_wave_change))
+                .with_system(call_early_clicked),
+        );
+    }
+}
+
+fn wave_preview_on_wave_change(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    waves: Res<Waves>,
+    panels: Query<(Entity, &WavePreviewRoot)>,
+) {
+    let mut up_to_date = false;
+    for (panel_entity, panel) in &panels {
+        if waves.next_wave().is_some() && panel.wave == waves.current {
+            up_to_date = true;
+        } else {
+            commands.entity(panel_entity).despawn_recursive();
+        }
+    }
+
+    if up_to_date {
+        return;
+    }
+    if waves.next_wave().is_some() {
+        let font = asset_server.load("fonts/Merriweather-Regular.ttf");
+        spawn_wave_preview(&mut commands, font, &game_assets, &waves);
+    }
+}
+
+fn spawn_wave_preview(
+    commands: &mut Commands,
+    font: Handle<Font>,
+    game_assets: &GameAssets,
+    waves: &Waves,
+) {
+    let text_style = TextStyle {
+        font,
+        font_size: 24.0,
+        color: Color::WHITE,
+    };
+    let trait_style = TextStyle {
+        font_size: 18.0,
+        color: Color::rgb(1.0, 0.8, 0.4),
+        ..text_style.clone()
+    };
+    let Some(wave) = waves.next_wave() else {
+        return;
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Percent(1.0),
+                    top: Val::Percent(8.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .insert(WavePreviewRoot {
+            wave: waves.current,
+        })
+        .insert(Name::new("Wave Preview"))
+        .with_children(|commands| {
+            commands.spawn(TextBundle::from_section(
+                format!("Next: wave {} of {}", waves.current + 1, waves.total()),
+                text_style.clone(),
+            ));
+
+            for group in &wave.groups {
+                commands
+                    .spawn(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            margin: UiRect::top(Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: Color::NONE.into(),
+                        ..default()
+                    })
+                    .with_children(|commands| {
+                        commands.spawn(ImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(32.0), Val::Px(32.0)),
+                                margin: UiRect::right(Val::Px(6.0)),
+                                ..default()
+                            },
+                            image: group.enemy_type.icon(game_assets).into(),
+                            ..default()
+                        });
+                        commands.spawn(TextBundle::from_section(
+                            format!("{} x{}", group.enemy_type.name(), group.count),
+                            text_style.clone(),
+                        ));
+                        let traits = group.enemy_type.traits();
+                        if !traits.is_empty() {
+                            commands.spawn(
+                                TextBundle::from_section(traits.join(", "), trait_style.clone())
+                                    .with_style(Style {
+                                        margin: UiRect::left(Val::Px(8.0)),
+                                        ..default()
+                                    }),
+                            );
+                        }
+                    });
+            }
+
+            // Filled in by update_wave_countdown
+            commands
+                .spawn(TextBundle::from_section("", text_style.clone()))
+                .insert(WaveCountdownText);
+            commands
+                .spawn(ButtonBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(8.0)),
+                        padding: UiRect::all(Val::Px(6.0)),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    background_color: Color::WHITE.into(),
+                    ..default()
+                })
+                .insert(CallEarlyButton)
+                .with_children(|commands| {
+                    commands
+                        .spawn(TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                color: Color::BLACK,
+                                ..text_style.clone()
+                            },
+                        ))
+                        .insert(CallEarlyText);
+                });
+        });
+}
+
+fn update_wave_countdown(
+    waves: Res<Waves>,
+    level: Res<Level>,
+    targets: Query<(), With<Target>>,
+    mut countdown_text: Query<&mut Text, (With<WaveCountdownText>, Without<CallEarlyText>)>,
+    mut call_early_text: Query<&mut Text, With<CallEarlyText>>,
+    mut call_early_button: Query<&mut Visibility, With<CallEarlyButton>>,
+) {
+    // The countdown only runs once the field is clear
+    let counting_down = targets.is_empty();
+    if let Ok(mut visibility) = call_early_button.get_single_mut() {
+        // Hidden buttons aren't clickable either
+        if visibility.is_visible != counting_down {
+            visibility.is_visible = counting_down;
+        }
+    }
+    if let Ok(mut text) = countdown_text.get_single_mut() {
+        text.sections[0].value = if counting_down {
+            format!("Starts in {:.0}s", waves.countdown.remaining_secs().ceil())
+        } else {
+            "Starts once this wave is cleared".to_string()
+        };
+    }
+    if let Ok(mut text) = call_early_text.get_single_mut() {
+        let bonus = waves.call_early_bonus(level.economy.call_early_bonus_per_second);
+        text.sections[0].value = format!("Call early (+{})", bonus);
+    }
+}
+
+fn call_early_clicked(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<CallEarlyButton>)>,
+    mut waves: ResMut<Waves>,
+    level: Res<Level>,
+    mut player: Query<&mut Player>,
+    targets: Query<(), With<Target>>,
+) {
+    // Calling a wave mid-fight would skip no countdown, so there is nothing to pay for
+    if !targets.is_empty() {
+        return;
+    }
+    for interaction in &interactions {
+        if matches!(interaction, Interaction::Clicked) && waves.next_wave().is_some() {
+            let bonus = waves.call_early_bonus(level.economy.call_early_bonus_per_second);
+            player.single_mut().funds.add(Currency::Gold, bonus);
+            waves.call_early();
+        }
+    }
+}